@@ -1,7 +1,7 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
-use crate::{cfg_parser, display};
+use crate::{cfg_parser, check, display, format, layout::Layout};
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -19,26 +19,54 @@ struct Args {
     height: Option<u32>,
 
     #[arg(short, long)]
+    position: Option<String>,
+
+    #[arg(short, long, global = true)]
     config: Option<PathBuf>,
+
+    /// auto, always or never
+    #[arg(long)]
+    color: Option<String>,
+
+    /// Print the fetched values as JSON instead of the formatted output
+    #[arg(long)]
+    json: bool,
+
+    /// Write the built-in default config to the config path for editing
+    #[arg(long)]
+    init_config: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check the config file for unknown keys, placeholders and invalid settings
+    Check,
 }
 
 pub fn parse() {
     let args = Args::parse();
 
-    let config_file = args.config.or_else(|| {
-        let mut path = std::env::home_dir().unwrap_or_else(|| {
-            eprintln!("Failed to get home directory!");
+    if let Some(Command::Check) = args.command {
+        if !check::check(args.config) {
             std::process::exit(1);
-        });
-        path.push(".config");
-        path.push("corrfetch");
-        path.push("config.toml");
-        Some(path)
-    });
-    let config = cfg_parser::read_config(config_file.unwrap_or_else(|| {
-        eprintln!("Failed to unwrap config file path!");
-        std::process::exit(1);
-    }));
+        }
+        return;
+    }
+
+    if args.init_config {
+        cfg_parser::init_config(args.config);
+        return;
+    }
+
+    let config = cfg_parser::load_config(args.config);
+
+    if args.json {
+        println!("{:#}", format::fetch_json(&config));
+        return;
+    }
 
     let logo_cfg = config.logo.as_ref();
 
@@ -57,13 +85,26 @@ pub fn parse() {
         .as_deref()
         .or_else(|| logo_cfg.and_then(|logo| logo.method.as_deref()));
 
+    let position = args
+        .position
+        .as_deref()
+        .or_else(|| logo_cfg.and_then(|logo| logo.position.as_deref()));
+
+    let layout = Layout::new(
+        args.color.as_deref(),
+        position,
+        logo_cfg.and_then(|logo| logo.gap),
+        logo_cfg.and_then(|logo| logo.padding),
+    );
+
     match method {
-        Some("ascii") => display::display_ascii(&config, path, height),
-        Some("img") => display::display(&config, path, width, height),
-        Some("none") => display::display_nologo(&config),
+        Some("ascii") => display::display_ascii(&config, &layout, path, height),
+        Some("img") => display::display(&config, &layout, path, width, height),
+        Some("builtin") => display::display_builtin(&config, &layout, path),
+        Some("none") => display::display_nologo(&config, &layout),
         Some(_) => eprintln!("Invalid method!"),
         None => {
-            display::display_nologo(&config);
+            display::display_nologo(&config, &layout);
         }
     }
 }
@@ -1,27 +1,204 @@
 use serde_derive::Deserialize;
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 use std::process::exit;
 use toml;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub keys: String,
-    pub format: HashMap<String, String>,
+    pub format: HashMap<String, Format>,
     pub separator: Option<char>,
     pub logo: Option<Logo>,
+    pub disk: Option<Disk>,
+    pub net: Option<Net>,
+    pub batt: Option<Batt>,
+    pub display: Option<Display>,
+    pub bar: Option<Bar>,
+    /// Roles (key, value, accent, separator, title, ...) and their colors.
+    pub colors: Option<HashMap<String, String>>,
+    pub swatches: Option<Swatches>,
+    pub timeout_ms: Option<u64>,
+    pub timeouts: Option<HashMap<String, u64>>,
+    pub timeout_placeholder: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// A key's format string, or a table with the format string and rules that
+/// pick another format string or color depending on the fetched values:
+///
+/// ```toml
+/// [format.ram]
+/// format = "{used}/{total} MiB"
+/// [[format.ram.when]]
+/// if = "used_percentage > 80"
+/// color = "red"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Format {
+    Plain(String),
+    Conditional {
+        format: String,
+        #[serde(default)]
+        when: Vec<Rule>,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Rule {
+    /// `<placeholder> <op> <value>`, with op one of > >= < <= == !=
+    #[serde(rename = "if")]
+    pub condition: String,
+    pub format: Option<String>,
+    pub color: Option<String>,
+}
+
+impl Format {
+    pub fn format_str(&self) -> &str {
+        match self {
+            Format::Plain(format) | Format::Conditional { format, .. } => format,
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        match self {
+            Format::Plain(_) => &[],
+            Format::Conditional { when, .. } => when,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct Logo {
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub method: Option<String>,
+    /// Image or .txt file, or the logo name for the builtin method.
+    #[serde(alias = "logo")]
     pub path: Option<String>,
     pub charset: Option<Vec<String>>,
+    /// left | right | top | bottom
+    pub position: Option<String>,
+    pub gap: Option<u32>,
+    pub padding: Option<u32>,
+    /// auto | kitty | iterm | sixel | blocks
+    pub protocol: Option<String>,
+    /// What to show when the image cannot be printed: ascii | none
+    pub fallback: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Disk {
+    pub mounts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Net {
+    pub show: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Batt {
+    pub show: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Display {
+    pub show: Option<String>,
+}
+
+/// How `{bar:placeholder}` gauges are drawn.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Bar {
+    pub width: Option<usize>,
+    pub filled: Option<char>,
+    pub empty: Option<char>,
+    /// Colors of the filled part, each used from its `from` percentage on.
+    pub colors: Option<Vec<BarColor>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BarColor {
+    pub from: f64,
+    pub color: String,
+}
+
+/// How the `colors` key draws the terminal colors.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Swatches {
+    pub glyph: Option<char>,
+    /// How many glyphs per color.
+    pub width: Option<usize>,
+    /// Whether to add a row with the bright colors.
+    pub bright: Option<bool>,
+}
+
+/// Used when there is no config file at the default location.
+pub const DEFAULT_CONFIG: &str = include_str!("../examples/default.toml");
+
+/// `$XDG_CONFIG_HOME`, or `~/.config` when it is unset.
+pub fn config_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::home_dir().map(|home| home.join(".config")))
+}
+
+/// `$XDG_CONFIG_HOME/corrfetch/config.toml`, or `~/.config/corrfetch/config.toml`
+/// when `$XDG_CONFIG_HOME` is unset.
+pub fn default_config_path() -> PathBuf {
+    let mut path = config_home().unwrap_or_else(|| {
+        eprintln!("Failed to get home directory!");
+        exit(1);
+    });
+    path.push("corrfetch");
+    path.push("config.toml");
+    path
+}
+
+/// Reads the given config file, or the one at the default location, falling
+/// back to the built-in config if the latter does not exist.
+pub fn load_config(config_file: Option<PathBuf>) -> Config {
+    match config_file {
+        Some(path) => read_config(path),
+        None => {
+            let path = default_config_path();
+            if path.exists() {
+                read_config(path)
+            } else {
+                toml::from_str(DEFAULT_CONFIG).expect("Built-in config is invalid!")
+            }
+        }
+    }
+}
+
+/// Writes the built-in config to `config_file`, or to the default location.
+pub fn init_config(config_file: Option<PathBuf>) {
+    let path = config_file.unwrap_or_else(default_config_path);
+
+    if path.exists() {
+        eprintln!("Config file `{path:?}` already exists, not overwriting it!");
+        exit(1);
+    }
+
+    if let Some(dir) = path.parent()
+        && let Err(e) = fs::create_dir_all(dir)
+    {
+        eprintln!("Could not create directory `{dir:?}`: {e}");
+        exit(1);
+    }
+
+    match fs::write(&path, DEFAULT_CONFIG) {
+        Ok(()) => println!("Wrote default config to `{path:?}`"),
+        Err(e) => {
+            eprintln!("Could not write file `{path:?}`: {e}");
+            exit(1);
+        }
+    }
 }
 
-pub fn read_config(config_file: std::path::PathBuf) -> Config {
+pub fn read_config(config_file: PathBuf) -> Config {
     let contents = match fs::read_to_string(&config_file) {
         Ok(c) => c,
         Err(e) => {
@@ -0,0 +1,614 @@
+use crate::cfg_parser::{self, Config};
+use crate::fetch::Fetcher;
+use crate::format::{self, Condition, Filter};
+use crate::{color, fetch, logos};
+use serde_derive::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs};
+use toml::Spanned;
+
+// Mirrors the parts of `Config` that are checked, keeping track of where in
+// the file each value came from.
+#[derive(Deserialize)]
+struct SpannedConfig {
+    keys: Spanned<String>,
+    format: Spanned<HashMap<String, Spanned<SpannedFormat>>>,
+    logo: Option<Spanned<SpannedLogo>>,
+    bar: Option<SpannedBar>,
+    colors: Option<HashMap<String, Spanned<String>>>,
+}
+
+#[derive(Deserialize)]
+struct SpannedBar {
+    colors: Option<Vec<SpannedBarColor>>,
+}
+
+#[derive(Deserialize)]
+struct SpannedBarColor {
+    color: Spanned<String>,
+}
+
+// A plain format string has the span of the whole value. This is not
+// `#[serde(untagged)]` like `cfg_parser::Format`, as that loses the spans.
+enum SpannedFormat {
+    Plain(String),
+    Conditional(SpannedFormatTable),
+}
+
+#[derive(Deserialize)]
+struct SpannedFormatTable {
+    format: Spanned<String>,
+    #[serde(default)]
+    when: Vec<SpannedRule>,
+}
+
+#[derive(Deserialize)]
+struct SpannedRule {
+    #[serde(rename = "if")]
+    condition: Spanned<String>,
+    format: Option<Spanned<String>>,
+    color: Option<Spanned<String>>,
+}
+
+impl<'de> serde::Deserialize<'de> for SpannedFormat {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = SpannedFormat;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a format string or table")
+            }
+
+            fn visit_str<E>(self, format: &str) -> Result<SpannedFormat, E> {
+                Ok(SpannedFormat::Plain(format.to_string()))
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                map: A,
+            ) -> Result<SpannedFormat, A::Error> {
+                let deserializer = serde::de::value::MapAccessDeserializer::new(map);
+                serde::Deserialize::deserialize(deserializer).map(SpannedFormat::Conditional)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[derive(Deserialize)]
+struct SpannedLogo {
+    method: Option<Spanned<String>>,
+    #[serde(alias = "logo")]
+    path: Option<Spanned<String>>,
+    charset: Option<Spanned<Vec<String>>>,
+    position: Option<Spanned<String>>,
+    protocol: Option<Spanned<String>>,
+    fallback: Option<Spanned<String>>,
+}
+
+/// A problem found in the config, at a 1-based line and column.
+struct Diagnostic {
+    line: usize,
+    column: usize,
+    level: &'static str,
+    msg: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line, self.column, self.level, self.msg
+        )
+    }
+}
+
+// Problems are collected by offset in the file, so they can be listed in file
+// order whichever check found them.
+struct Diagnostics<'a> {
+    contents: &'a str,
+    found: Vec<(usize, &'static str, String)>,
+}
+
+impl Diagnostics<'_> {
+    fn error(&mut self, offset: usize, msg: String) {
+        self.found.push((offset, "error", msg));
+    }
+
+    fn warning(&mut self, offset: usize, msg: String) {
+        self.found.push((offset, "warning", msg));
+    }
+
+    fn into_sorted(mut self) -> Vec<Diagnostic> {
+        self.found.sort_by_key(|(offset, _, _)| *offset);
+
+        self.found
+            .into_iter()
+            .map(|(offset, level, msg)| {
+                let before = &self.contents[..offset.min(self.contents.len())];
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                Diagnostic {
+                    line: before.matches('\n').count() + 1,
+                    column: before[line_start..].chars().count() + 1,
+                    level,
+                    msg,
+                }
+            })
+            .collect()
+    }
+
+    // Offset of `needle` in the source of a value, searching from `from`
+    // (relative to the start of the value).
+    fn find(&self, span: std::ops::Range<usize>, from: usize, needle: &str) -> usize {
+        let source = self.contents.get(span.start + from..span.end).unwrap_or("");
+        span.start + from + source.find(needle).unwrap_or(0)
+    }
+}
+
+fn check_keys(diag: &mut Diagnostics, parsed: &Config, config: &SpannedConfig) {
+    let keys = &config.keys;
+    let format = config.format.get_ref();
+
+    let mut seen = HashSet::new();
+    let mut from = 0;
+    for key in keys.get_ref().split(",") {
+        let name = key.trim();
+        let offset = diag.find(keys.span(), from, name);
+        from = offset - keys.span().start + name.len();
+
+        if matches!(name, "separator" | "colors") || !seen.insert(name) {
+            continue;
+        }
+
+        let Some(fetcher) = fetch::get(name) else {
+            // Anything that does not look like a key name is printed as text.
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                continue;
+            }
+            if name.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+                diag.error(offset, format!("unknown key `{name}`"));
+            } else {
+                // Likely a typo like `Ram` or `cpu2`, but could be meant as text.
+                let lower = name.to_ascii_lowercase();
+                let hint = match fetch::get(&lower) {
+                    Some(_) => format!(", did you mean `{lower}`?"),
+                    None => String::new(),
+                };
+                diag.warning(
+                    offset,
+                    format!("unknown key `{name}` is printed as text{hint}"),
+                );
+            }
+            continue;
+        };
+
+        let Some(format) = format.get(name) else {
+            diag.error(
+                config.format.span().start,
+                format!("key `{name}` has no entry in the `[format]` section"),
+            );
+            continue;
+        };
+
+        match format.get_ref() {
+            SpannedFormat::Plain(format_str) => {
+                check_format_str(diag, parsed, fetcher, format_str, format.span())
+            }
+            SpannedFormat::Conditional(table) => check_rules(diag, parsed, config, fetcher, table),
+        }
+    }
+}
+
+fn check_format_str(
+    diag: &mut Diagnostics,
+    parsed: &Config,
+    fetcher: &dyn Fetcher,
+    format_str: &str,
+    span: Range<usize>,
+) {
+    let mut unknown: Vec<_> = fetch::placeholders_in(format_str)
+        .into_iter()
+        .filter(|placeholder| !fetcher.placeholders().contains(&placeholder.as_str()))
+        .collect();
+    unknown.sort();
+
+    let mut valid = unknown.is_empty();
+    for placeholder in unknown {
+        diag.error(
+            diag.find(span.clone(), 0, &format!("{{{placeholder}")),
+            format!(
+                "unknown placeholder `{{{placeholder}}}` for key `{}` (available: {})",
+                fetcher.name(),
+                fetcher.placeholders().join(", ")
+            ),
+        );
+    }
+
+    for filter in format::filters_in(format_str) {
+        if let Err(e) = Filter::parse(&filter) {
+            diag.error(diag.find(span.clone(), 0, &format!("|{filter}")) + 1, e);
+            valid = false;
+        }
+    }
+
+    // Only once the placeholders and filters are fine, as strfmt would report
+    // those again.
+    if valid && let Err(e) = format::validate(parsed, fetcher, format_str) {
+        diag.error(span.start, format!("invalid format string: {e}"));
+    }
+}
+
+fn check_rules(
+    diag: &mut Diagnostics,
+    parsed: &Config,
+    config: &SpannedConfig,
+    fetcher: &dyn Fetcher,
+    table: &SpannedFormatTable,
+) {
+    check_format_str(
+        diag,
+        parsed,
+        fetcher,
+        table.format.get_ref(),
+        table.format.span(),
+    );
+
+    for rule in &table.when {
+        let condition = &rule.condition;
+        match Condition::parse(condition.get_ref()) {
+            Some(parsed) if !fetcher.placeholders().contains(&parsed.placeholder) => diag.error(
+                condition.span().start,
+                format!(
+                    "unknown placeholder `{}` in condition for key `{}` (available: {})",
+                    parsed.placeholder,
+                    fetcher.name(),
+                    fetcher.placeholders().join(", ")
+                ),
+            ),
+            Some(_) => {}
+            None => diag.error(
+                condition.span().start,
+                format!(
+                    "invalid condition `{}` (expected `<placeholder> <op> <value>`, op one of > >= < <= == !=)",
+                    condition.get_ref()
+                ),
+            ),
+        }
+
+        if let Some(format_str) = &rule.format {
+            check_format_str(
+                diag,
+                parsed,
+                fetcher,
+                format_str.get_ref(),
+                format_str.span(),
+            );
+        }
+
+        if let Some(color) = &rule.color {
+            check_color(diag, config, color);
+        }
+    }
+}
+
+// Either a role from `[colors]` or a color.
+fn check_color(diag: &mut Diagnostics, config: &SpannedConfig, color: &Spanned<String>) {
+    let name = color.get_ref();
+    let is_role = config
+        .colors
+        .as_ref()
+        .is_some_and(|roles| roles.contains_key(name));
+
+    if !is_role && color::sgr(name, true).is_none() {
+        let names: Vec<_> = color::NAMES.iter().map(|(name, _)| *name).collect();
+        diag.error(
+            color.span().start,
+            format!(
+                "unknown color `{name}` (expected a role from `[colors]`, 0-255, #rrggbb or one of: {})",
+                names.join(", ")
+            ),
+        );
+    }
+}
+
+fn check_logo(diag: &mut Diagnostics, logo: &Spanned<SpannedLogo>) {
+    let method = logo.get_ref().method.as_ref();
+
+    let choices = [
+        (
+            "position",
+            &logo.get_ref().position,
+            "left, right, top or bottom",
+        ),
+        (
+            "protocol",
+            &logo.get_ref().protocol,
+            "auto, kitty, iterm, sixel or blocks",
+        ),
+        ("fallback", &logo.get_ref().fallback, "ascii or none"),
+    ];
+    for (name, value, expected) in choices {
+        if let Some(value) = value
+            && !expected
+                .split([',', ' '])
+                .any(|choice| choice == value.get_ref())
+        {
+            diag.error(
+                value.span().start,
+                format!(
+                    "invalid logo {name} `{}` (expected {expected})",
+                    value.get_ref()
+                ),
+            );
+        }
+    }
+
+    if let Some(method) = method
+        && !matches!(
+            method.get_ref().as_str(),
+            "none" | "img" | "ascii" | "builtin"
+        )
+    {
+        diag.error(
+            method.span().start,
+            format!(
+                "invalid logo method `{}` (expected none, img, ascii or builtin)",
+                method.get_ref()
+            ),
+        );
+    }
+
+    if method.is_some_and(|m| m.get_ref() == "builtin") {
+        if let Some(name) = &logo.get_ref().path
+            && logos::get(name.get_ref()).is_none()
+        {
+            let names: Vec<_> = logos::LOGOS.iter().map(|logo| logo.names[0]).collect();
+            diag.error(
+                name.span().start,
+                format!(
+                    "unknown builtin logo `{}` (available: {})",
+                    name.get_ref(),
+                    names.join(", ")
+                ),
+            );
+        }
+        return;
+    }
+
+    let needs_path = method.is_some_and(|m| matches!(m.get_ref().as_str(), "img" | "ascii"));
+    match &logo.get_ref().path {
+        // Only img and ascii read the file.
+        Some(path) if needs_path && !Path::new(path.get_ref()).exists() => diag.error(
+            path.span().start,
+            format!("logo file `{}` does not exist", path.get_ref()),
+        ),
+        // The path can also be given with --logo.
+        None if needs_path => diag.warning(
+            logo.span().start,
+            String::from("logo method needs a `path` to an image or .txt file, or --logo"),
+        ),
+        _ => {}
+    }
+
+    if let Some(charset) = &logo.get_ref().charset
+        && charset.get_ref().is_empty()
+    {
+        diag.error(charset.span().start, String::from("logo charset is empty"));
+    }
+}
+
+/// Checks the given config file (or the one at the default location) and
+/// prints every problem found. Returns whether the config is valid.
+pub fn check(config_file: Option<PathBuf>) -> bool {
+    // The built-in config only stands in for a missing default one.
+    if let Some(path) = &config_file
+        && !path.exists()
+    {
+        eprintln!("Config file `{path:?}` does not exist!");
+        return false;
+    }
+    let path = config_file.unwrap_or_else(cfg_parser::default_config_path);
+
+    let (file, contents) = if path.exists() {
+        match fs::read_to_string(&path) {
+            Ok(contents) => (path.display().to_string(), contents),
+            Err(e) => {
+                eprintln!("Could not read file `{path:?}`: {e}");
+                return false;
+            }
+        }
+    } else {
+        println!("No config file at `{path:?}`, checking the built-in config.");
+        (
+            String::from("<built-in>"),
+            cfg_parser::DEFAULT_CONFIG.to_string(),
+        )
+    };
+
+    let diagnostics = match diagnose(&contents) {
+        Ok(diagnostics) => diagnostics,
+        Err(e) => {
+            eprintln!("{file}: error: {e}");
+            return false;
+        }
+    };
+
+    for diagnostic in &diagnostics {
+        eprintln!("{file}:{diagnostic}");
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == "error")
+        .count();
+
+    if errors == 0 {
+        println!("{file}: no problems found");
+        true
+    } else {
+        eprintln!("{file}: {errors} error(s) found");
+        false
+    }
+}
+
+/// Checks the contents of a config file. Returns every problem found in file
+/// order, or the error if it is not a valid config at all.
+fn diagnose(contents: &str) -> Result<Vec<Diagnostic>, String> {
+    // Catches type errors, which already come with a location.
+    let parsed: Config = toml::from_str(contents).map_err(|e| e.to_string())?;
+    let config: SpannedConfig = toml::from_str(contents).map_err(|e| e.to_string())?;
+
+    let mut diag = Diagnostics {
+        contents,
+        found: Vec::new(),
+    };
+
+    check_keys(&mut diag, &parsed, &config);
+    if let Some(logo) = &config.logo {
+        check_logo(&mut diag, logo);
+    }
+    for bar_color in config
+        .bar
+        .iter()
+        .flat_map(|bar| bar.colors.iter().flatten())
+    {
+        check_color(&mut diag, &config, &bar_color.color);
+    }
+    for role in config.colors.iter().flat_map(|roles| roles.values()) {
+        if color::sgr(role.get_ref(), true).is_none() {
+            diag.error(
+                role.span().start,
+                format!(
+                    "invalid color `{}` (expected 0-255, #rrggbb or a color name like bright_red)",
+                    role.get_ref()
+                ),
+            );
+        }
+    }
+
+    Ok(diag.into_sorted())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostics(config: &str) -> Vec<String> {
+        diagnose(config)
+            .unwrap()
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn valid_config() {
+        let config = r#"
+keys = "hostname, separator, ram"
+[format]
+hostname = "{host}"
+ram = "{used:>6}/{total|gib:1} {bar:used_percentage}"
+"#;
+        assert!(diagnostics(config).is_empty());
+    }
+
+    #[test]
+    fn built_in_config() {
+        assert!(diagnostics(cfg_parser::DEFAULT_CONFIG).is_empty());
+    }
+
+    #[test]
+    fn unknown_key() {
+        let config = r#"
+keys = "hostname, hostnme"
+[format]
+hostname = "{host}"
+"#;
+        assert_eq!(diagnostics(config), ["2:19: error: unknown key `hostnme`"]);
+    }
+
+    #[test]
+    fn key_like_text() {
+        let config = r#"
+keys = "Ram, cpu2, ─── text ───"
+[format]
+"#;
+        assert_eq!(
+            diagnostics(config),
+            [
+                "2:9: warning: unknown key `Ram` is printed as text, did you mean `ram`?",
+                "2:14: warning: unknown key `cpu2` is printed as text",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_placeholder() {
+        let config = r#"
+keys = "hostname"
+[format]
+hostname = "<{hots}>"
+"#;
+        assert_eq!(
+            diagnostics(config),
+            ["4:14: error: unknown placeholder `{hots}` for key `hostname` (available: host)"]
+        );
+    }
+
+    #[test]
+    fn unknown_placeholder_in_rule() {
+        let config = r#"
+keys = "hostname"
+[format.hostname]
+format = "{host}"
+[[format.hostname.when]]
+if = "hots == x"
+format = "{hots}!"
+"#;
+        assert_eq!(
+            diagnostics(config),
+            [
+                "6:6: error: unknown placeholder `hots` in condition for key `hostname` (available: host)",
+                "7:11: error: unknown placeholder `{hots}` for key `hostname` (available: host)",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_format_spec() {
+        let config = r#"
+keys = "ram"
+[format]
+ram = "{used:q}"
+"#;
+        assert_eq!(
+            diagnostics(config),
+            ["4:7: error: invalid format string: Invalid type specifier: 'q'"]
+        );
+    }
+
+    #[test]
+    fn sorted_by_location() {
+        // Keys are checked in order, so `ram` is found before `hostname`.
+        let config = r#"
+keys = "ram, hostname"
+[format]
+hostname = "{hots}"
+ram = "{usd}"
+"#;
+        let found = diagnostics(config);
+        assert_eq!(found.len(), 2);
+        assert!(found[0].starts_with("4:13: error: unknown placeholder `{hots}`"));
+        assert!(found[1].starts_with("5:8: error: unknown placeholder `{usd}`"));
+    }
+
+    #[test]
+    fn missing_config_file() {
+        assert!(!check(Some(PathBuf::from("/nonexistent/corrfetch.toml"))));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::cfg_parser::Config;
+use std::collections::HashMap;
+
+/// The 16 terminal colors, with their ANSI SGR codes.
+pub const NAMES: &[(&str, &str)] = &[
+    ("black", "30"),
+    ("red", "31"),
+    ("green", "32"),
+    ("yellow", "33"),
+    ("blue", "34"),
+    ("magenta", "35"),
+    ("cyan", "36"),
+    ("white", "37"),
+    ("bright_black", "90"),
+    ("bright_red", "91"),
+    ("bright_green", "92"),
+    ("bright_yellow", "93"),
+    ("bright_blue", "94"),
+    ("bright_magenta", "95"),
+    ("bright_cyan", "96"),
+    ("bright_white", "97"),
+];
+
+/// The SGR code of a color name, 256-color index (`0`-`255`) or `#rrggbb`.
+/// Without `truecolor`, `#rrggbb` is downgraded to the closest of the 256
+/// colors.
+pub fn sgr(color: &str, truecolor: bool) -> Option<String> {
+    if let Some((_, code)) = NAMES.iter().find(|(name, _)| *name == color) {
+        return Some(code.to_string());
+    }
+
+    if let Ok(index) = color.parse::<u8>() {
+        return Some(format!("38;5;{index}"));
+    }
+
+    let hex = color.strip_prefix('#').filter(|hex| hex.len() == 6)?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+
+    if truecolor {
+        Some(format!("38;2;{r};{g};{b}"))
+    } else {
+        // The 6x6x6 cube of the 256-color palette.
+        let level = |c: u8| (c as u16 * 5 + 127) / 255;
+        Some(format!(
+            "38;5;{}",
+            16 + 36 * level(r) + 6 * level(g) + level(b)
+        ))
+    }
+}
+
+/// Whether the terminal advertises 24-bit color in `$COLORTERM`.
+fn truecolor() -> bool {
+    std::env::var("COLORTERM").is_ok_and(|term| term == "truecolor" || term == "24bit")
+}
+
+/// The `[colors]` roles (like key, value, accent, separator and title), and
+/// whether truecolor can be used.
+pub struct Palette {
+    roles: HashMap<String, String>,
+    truecolor: bool,
+}
+
+impl Palette {
+    pub fn new(config: &Config) -> Self {
+        Palette {
+            roles: config.colors.clone().unwrap_or_default(),
+            truecolor: truecolor(),
+        }
+    }
+
+    /// The SGR code of a role, or of a color given directly.
+    pub fn sgr(&self, name: &str) -> Option<String> {
+        let color = self.roles.get(name).map_or(name, |color| color.as_str());
+        sgr(color, self.truecolor)
+    }
+
+    /// `text` in the color of `name`, or as it is if there is no such color.
+    pub fn paint(&self, name: &str, text: &str) -> String {
+        match self.sgr(name) {
+            Some(code) => format!("\u{1b}[{code}m{text}\u{1b}[0m"),
+            None => text.to_string(),
+        }
+    }
+
+    /// Replaces `<name>...</>` markup, where name is a role or a color, with
+    /// ANSI escapes. Tags can be nested, and tags that are not a color are
+    /// left as they are.
+    pub fn markup(&self, text: &str) -> String {
+        let re = regex::Regex::new(r"<(/|#?\w+)>").unwrap();
+        let mut open: Vec<String> = Vec::new();
+
+        re.replace_all(text, |cap: &regex::Captures| {
+            if &cap[1] == "/" {
+                open.pop();
+                // Back to the color of the enclosing tag, if any.
+                match open.last() {
+                    Some(code) => format!("\u{1b}[0m\u{1b}[{code}m"),
+                    None => String::from("\u{1b}[0m"),
+                }
+            } else if let Some(code) = self.sgr(&cap[1]) {
+                let escape = format!("\u{1b}[{code}m");
+                open.push(code);
+                escape
+            } else {
+                cap[0].to_string()
+            }
+        })
+        .into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sgr_names_and_indices() {
+        assert_eq!(sgr("red", false).as_deref(), Some("31"));
+        assert_eq!(sgr("bright_cyan", true).as_deref(), Some("96"));
+        assert_eq!(sgr("0", false).as_deref(), Some("38;5;0"));
+        assert_eq!(sgr("208", true).as_deref(), Some("38;5;208"));
+        assert_eq!(sgr("256", false), None);
+        assert_eq!(sgr("-1", false), None);
+        assert_eq!(sgr("Red", false), None);
+        assert_eq!(sgr("purple", false), None);
+    }
+
+    #[test]
+    fn sgr_hex() {
+        assert_eq!(sgr("#ff8000", true).as_deref(), Some("38;2;255;128;0"));
+        assert_eq!(sgr("#FF8000", true).as_deref(), Some("38;2;255;128;0"));
+        assert_eq!(sgr("#ff800", true), None);
+        assert_eq!(sgr("#ff80000", true), None);
+        assert_eq!(sgr("ff8000", true), None);
+        assert_eq!(sgr("#gg8000", true), None);
+        assert_eq!(sgr("#ffé00", true), None);
+    }
+
+    #[test]
+    fn sgr_hex_downgraded_to_256_colors() {
+        assert_eq!(sgr("#000000", false).as_deref(), Some("38;5;16"));
+        assert_eq!(sgr("#ffffff", false).as_deref(), Some("38;5;231"));
+        assert_eq!(sgr("#ff8000", false).as_deref(), Some("38;5;214"));
+        assert_eq!(sgr("#0000ff", false).as_deref(), Some("38;5;21"));
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{cfg_parser::Config, format};
+use crate::layout::{Layout, Position, display_width};
+use crate::{cfg_parser::Config, format, logos};
 use crossterm::{
     cursor::{MoveRight, MoveToNextLine, MoveToPreviousLine},
     execute,
@@ -7,11 +8,99 @@ use rascii_art::{RenderOptions, render_to};
 use std::fs::File;
 use std::io::{self, BufRead, stdout};
 use std::path::Path;
-use unicode_width::UnicodeWidthStr;
 
-pub fn display(config: &Config, logo: Option<String>, width: Option<u32>, height: Option<u32>) {
+/// Picks the viuer printers to enable for `protocol` (auto, kitty, iterm,
+/// sixel or blocks), as (kitty, iterm, sixel). Returns `None` if the terminal
+/// does not support the requested protocol.
+fn image_protocol(protocol: &str) -> Option<(bool, bool, bool)> {
+    #[cfg(feature = "sixel")]
+    let sixel = viuer::is_sixel_supported();
+    #[cfg(not(feature = "sixel"))]
+    let sixel = false;
+
+    let supported = match protocol {
+        "auto" => true,
+        "kitty" => viuer::get_kitty_support() != viuer::KittySupport::None,
+        "iterm" => viuer::is_iterm_supported(),
+        "sixel" => sixel,
+        "blocks" => true,
+        other => {
+            eprintln!(
+                "Invalid image protocol `{other}`, expected auto, kitty, iterm, sixel or blocks!"
+            );
+            return None;
+        }
+    };
+    if !supported {
+        eprintln!("Image protocol `{protocol}` is not supported by this terminal!");
+        return None;
+    }
+
+    Some(match protocol {
+        "kitty" => (true, false, false),
+        "iterm" => (false, true, false),
+        "sixel" => (false, false, true),
+        "blocks" => (false, false, false),
+        _ => (true, true, true),
+    })
+}
+
+/// Used when the image cannot be shown: the image converted to ascii, or no
+/// logo at all with `fallback = "none"`.
+fn fallback_logo(
+    config: &Config,
+    logo: Option<String>,
+    height: Option<u32>,
+    lines: usize,
+) -> String {
+    let fallback = config
+        .logo
+        .as_ref()
+        .and_then(|logo| logo.fallback.as_deref());
+
+    match fallback {
+        Some("none") => String::new(),
+        _ => ascii_logo(config, logo, ascii_height(height, lines)).unwrap_or_default(),
+    }
+}
+
+fn display_fallback(
+    config: &Config,
+    layout: &Layout,
+    logo: Option<String>,
+    height: Option<u32>,
+    fetch_text: &[String],
+) {
+    let ascii = fallback_logo(config, logo, height, fetch_text.len());
+    layout.print(&layout.compose(&ascii, fetch_text));
+}
+
+pub fn display(
+    config: &Config,
+    layout: &Layout,
+    logo: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+) {
     let fetch_text = format::fetch(config);
 
+    // Images need a terminal, and cursor movement to put the text beside them.
+    if !layout.tty || !layout.color {
+        display_fallback(config, layout, logo, height, &fetch_text);
+        return;
+    }
+
+    let protocol = config
+        .logo
+        .as_ref()
+        .and_then(|logo| logo.protocol.as_deref())
+        .unwrap_or("auto");
+    #[cfg_attr(not(feature = "sixel"), allow(unused_variables))]
+    let Some((use_kitty, use_iterm, use_sixel)) = image_protocol(protocol) else {
+        display_fallback(config, layout, logo, height, &fetch_text);
+        return;
+    };
+
     let (conf_height, conf_width) = match (width, height) {
         (Some(w), h) => (Some(h.unwrap_or(w)), Some(w * 2)),
         (None, Some(h)) => (Some(h), Some(h * 2)),
@@ -25,41 +114,96 @@ pub fn display(config: &Config, logo: Option<String>, width: Option<u32>, height
         }
     };
 
-    let conf = viuer::Config {
-        width: conf_width,
-        height: conf_height,
-        absolute_offset: false,
-        restore_cursor: false,
-        ..Default::default()
+    let Some(logo) = logo else {
+        eprintln!("Please provide a path to image file!");
+        layout.print(&layout.compose("", &fetch_text));
+        return;
     };
-    viuer::print_from_file(
-        match logo {
-            Some(logo) => logo,
-            None => {
-                eprintln!("Please provide a path to image file!");
-                return;
-            }
-        },
-        &conf,
-    )
-    .expect("Image printing failed.");
-
-    execute!(stdout(), MoveToPreviousLine(conf_height.unwrap() as u16)).unwrap();
-
-    fetch_text.iter().for_each(|s| {
-        execute!(stdout(), MoveRight(conf_width.unwrap() as u16 + 2)).unwrap();
-        println!("{s}")
-    });
-
-    let move_by = {
-        if fetch_text.len() < conf_height.unwrap() as usize {
-            conf_height.unwrap() as u16 - fetch_text.len() as u16
-        } else {
-            fetch_text.len() as u16
+
+    let (image_height, image_width) = (conf_height.unwrap() as u16, conf_width.unwrap() as u16);
+    let padding = layout.padding as u16;
+    let gap = layout.gap as u16;
+    let text_width = fetch_text
+        .iter()
+        .map(|s| display_width(s))
+        .max()
+        .unwrap_or(0) as u16;
+
+    let print_image = |x: u16, y: i16| {
+        let conf = viuer::Config {
+            width: conf_width,
+            height: conf_height,
+            absolute_offset: false,
+            restore_cursor: false,
+            x,
+            y,
+            use_kitty,
+            use_iterm,
+            #[cfg(feature = "sixel")]
+            use_sixel,
+            ..Default::default()
+        };
+        let printed = viuer::print_from_file(&logo, &conf);
+        if let Err(e) = &printed {
+            eprintln!("Image printing failed: {e}");
         }
+        printed.is_ok()
+    };
+    let print_text = |offset: u16| {
+        fetch_text.iter().for_each(|s| {
+            if offset > 0 {
+                execute!(stdout(), MoveRight(offset)).unwrap();
+            }
+            println!("{s}")
+        });
     };
 
-    execute!(stdout(), MoveToNextLine(move_by)).unwrap();
+    match layout.position {
+        Position::Left => {
+            if !print_image(padding, 0) {
+                display_fallback(config, layout, Some(logo.clone()), height, &fetch_text);
+                return;
+            }
+            execute!(stdout(), MoveToPreviousLine(image_height)).unwrap();
+            print_text(padding + image_width + gap);
+            if (fetch_text.len() as u16) < image_height {
+                execute!(
+                    stdout(),
+                    MoveToNextLine(image_height - fetch_text.len() as u16)
+                )
+                .unwrap();
+            }
+        }
+        Position::Right => {
+            print_text(padding);
+            // The text is already out, so there is nothing to fall back to.
+            if print_image(padding + text_width + gap, -(fetch_text.len() as i16))
+                && fetch_text.len() as u16 > image_height
+            {
+                execute!(
+                    stdout(),
+                    MoveToNextLine(fetch_text.len() as u16 - image_height)
+                )
+                .unwrap();
+            }
+        }
+        Position::Top => {
+            if !print_image(padding, 0) {
+                display_fallback(config, layout, Some(logo.clone()), height, &fetch_text);
+                return;
+            }
+            (0..gap).for_each(|_| println!());
+            print_text(padding);
+        }
+        Position::Bottom => {
+            print_text(padding);
+            // The text is already out, so only the logo falls back.
+            if !print_image(padding, gap as i16) {
+                let ascii = fallback_logo(config, Some(logo.clone()), height, fetch_text.len());
+                layout.print(&layout.compose(&ascii, &[]));
+            }
+        }
+    }
 }
 
 fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
@@ -69,17 +213,9 @@ where
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
 }
-pub fn display_ascii(config: &Config, logo: Option<String>, height: Option<u32>) {
-    let fetch_text = format::fetch(config);
-    let mut ascii = String::new();
 
-    let conf_height = if let Some(h) = height {
-        h
-    } else if fetch_text.len() <= 10 {
-        12
-    } else {
-        fetch_text.len() as u32 + 4
-    };
+fn ascii_logo(config: &Config, logo: Option<String>, height: u32) -> Option<String> {
+    let mut ascii = String::new();
 
     if let Some(logo) = logo {
         if !logo.ends_with(".txt") {
@@ -91,15 +227,17 @@ pub fn display_ascii(config: &Config, logo: Option<String>, height: Option<u32>)
                 .map(|c| c.iter().map(|s| s.as_str()).collect())
                 .unwrap_or_else(|| vec![".", ",", "-", "*", "£", "$", "#"]);
 
-            render_to(
+            if let Err(e) = render_to(
                 logo,
                 &mut ascii,
                 &RenderOptions::new()
-                    .height(conf_height as u32)
+                    .height(height)
                     .colored(true)
                     .charset(&charset),
-            )
-            .unwrap();
+            ) {
+                eprintln!("Failed to convert image to ascii: {e}");
+                return None;
+            }
         } else if let Ok(lines) = read_lines(logo) {
             for line in lines.map_while(Result::ok) {
                 ascii.push_str(&line);
@@ -108,37 +246,57 @@ pub fn display_ascii(config: &Config, logo: Option<String>, height: Option<u32>)
         }
     } else {
         eprintln!("Please provide a path to image or .txt file!");
-        return;
+        return None;
     }
 
-    let width = ascii
-        .lines()
-        .map(|s| console::strip_ansi_codes(s).width())
-        .max()
-        .unwrap() as u16;
-    let height = ascii.lines().count() as u16;
+    Some(ascii)
+}
 
-    ascii.lines().for_each(|s| println!("{s}"));
+pub fn display_ascii(config: &Config, layout: &Layout, logo: Option<String>, height: Option<u32>) {
+    print_ascii(config, layout, logo, height, &format::fetch(config));
+}
 
-    execute!(stdout(), MoveToPreviousLine(height)).unwrap();
+fn print_ascii(
+    config: &Config,
+    layout: &Layout,
+    logo: Option<String>,
+    height: Option<u32>,
+    fetch_text: &[String],
+) {
+    let ascii =
+        ascii_logo(config, logo, ascii_height(height, fetch_text.len())).unwrap_or_default();
+    layout.print(&layout.compose(&ascii, fetch_text));
+}
 
-    fetch_text.iter().for_each(|s| {
-        execute!(stdout(), MoveRight(width + 2)).unwrap();
-        println!("{s}")
-    });
+/// The configured height, or one that fits `lines` lines of text.
+fn ascii_height(height: Option<u32>, lines: usize) -> u32 {
+    if let Some(h) = height {
+        h
+    } else if lines <= 10 {
+        12
+    } else {
+        lines as u32 + 4
+    }
+}
 
-    let move_by = {
-        if fetch_text.len() < height as usize {
-            height - fetch_text.len() as u16
-        } else {
-            fetch_text.len() as u16
-        }
+/// Prints a bundled distro logo, detected from /etc/os-release unless `name`
+/// picks one.
+pub fn display_builtin(config: &Config, layout: &Layout, name: Option<String>) {
+    let logo = match name {
+        Some(name) => match logos::get(&name) {
+            Some(logo) => logo,
+            None => {
+                eprintln!("No builtin logo named `{name}`!");
+                layout.print(&layout.compose("", &format::fetch(config)));
+                return;
+            }
+        },
+        None => logos::detect(),
     };
-    execute!(stdout(), MoveToNextLine(move_by)).unwrap();
-}
 
-pub fn display_nologo(config: &Config) {
-    let fetch_text = format::fetch(config);
+    layout.print(&layout.compose(&logo.render(), &format::fetch(config)));
+}
 
-    fetch_text.iter().for_each(|s| println!("{s}"));
+pub fn display_nologo(config: &Config, layout: &Layout) {
+    layout.print(&layout.compose("", &format::fetch(config)));
 }
@@ -1,396 +1,369 @@
-use std::{env, fs, path::Path, process::Command};
-
-pub struct Batt {
-    pub level: String,
-    pub status: String,
-}
-
-pub struct Ram {
-    pub total: String,
-    pub used: String,
-    pub free: String,
-    pub used_percentage: String,
-    pub free_percentage: String,
-    pub swap_total: String,
-    pub swap_used: String,
-    pub swap_free: String,
-    pub swap_used_percentage: String,
-    pub swap_free_percentage: String,
+use crate::cfg_parser::Config;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+use std::{fmt, fs, io, path::Path, process::Command, thread};
+
+mod batt;
+mod cpu;
+mod de;
+mod disk;
+mod display;
+mod distro;
+mod gpu;
+mod hostname;
+mod kernel;
+mod lifetime;
+mod net;
+mod pkgs;
+mod ram;
+mod shell;
+mod swap;
+mod terminal;
+mod uptime;
+mod username;
+mod wm;
+
+/// A single fetched value, kept typed until it is rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    /// Shown with one decimal.
+    Float(f64),
+    /// A size in bytes, shown in the given unit.
+    Size(u64, Unit),
+    Text(String),
 }
 
-pub struct Swap {
-    pub total: String,
-    pub used: String,
-    pub free: String,
-    pub free_percentage: String,
-    pub used_percentage: String,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    KiB,
+    MiB,
+    GiB,
 }
 
-pub struct Uptime {
-    pub d: i32,
-    pub h: i32,
-    pub m: i32,
-    pub s: i32,
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x:.1}"),
+            Value::Size(bytes, Unit::KiB) => write!(f, "{}", bytes / 1024),
+            Value::Size(bytes, Unit::MiB) => write!(f, "{}", bytes / 1024 / 1024),
+            Value::Size(bytes, Unit::GiB) => {
+                write!(f, "{:.1}", *bytes as f64 / 1024.0 / 1024.0 / 1024.0)
+            }
+            Value::Text(s) => write!(f, "{s}"),
+        }
+    }
 }
 
-pub struct Lifetime {
-    pub y: i32,
-    pub mo: i32,
-    pub d: i32,
-    pub h: i32,
-    pub m: i32,
-    pub s: i32,
+/// Numbers are kept as numbers, sizes are given in bytes.
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Int(i) => serializer.serialize_i64(*i),
+            Value::Float(x) => serializer.serialize_f64(*x),
+            Value::Size(bytes, _) => serializer.serialize_u64(*bytes),
+            Value::Text(s) => serializer.serialize_str(s),
+        }
+    }
 }
 
-pub struct De {
-    pub de: String,
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Text(s)
+    }
 }
 
-pub struct Kernel {
-    pub kernel: String,
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Text(s.to_string())
+    }
 }
 
-pub struct Distro {
-    pub distro: String,
-    pub arch: String,
-    pub version: String,
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::Int(i)
+    }
 }
 
-pub struct Username {
-    pub user: String,
-    pub host: String,
-}
+/// Placeholder values for one output line.
+pub type Fields = BTreeMap<&'static str, Value>;
 
-pub struct Hostname {
-    pub host: String,
+#[derive(Debug)]
+pub enum FetchError {
+    Read { path: String, source: io::Error },
+    Command { program: String, source: io::Error },
+    Env(&'static str),
+    Parse { path: String, what: &'static str },
+    Unsupported(String),
 }
 
-pub struct Shell {
-    pub shell: String,
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Read { path, source } => write!(f, "Failed to read {path}: {source}"),
+            FetchError::Command { program, source } => {
+                write!(f, "Failed to run `{program}`: {source}")
+            }
+            FetchError::Env(var) => write!(f, "Failed to get ${var} env variable!"),
+            FetchError::Parse { path, what } => write!(f, "Failed to parse {what} from {path}!"),
+            FetchError::Unsupported(msg) => write!(f, "{msg}"),
+        }
+    }
 }
 
-pub struct Pkgs {
-    pub native: String,
-    pub flatpak: String,
-    pub snap: String,
-    pub manager: String,
-}
+impl std::error::Error for FetchError {}
 
-pub struct Separator {
-    pub separator: String,
-}
+/// Returns the names of all `{placeholder}`s in a format string, including
+/// those with a format spec like `{native:>6}`, filters like `{total|gib:1}`
+/// and bars like `{bar:used_percentage}`.
+pub fn placeholders_in(format: &str) -> HashSet<String> {
+    let re = regex::Regex::new(r"\{(?:bar:)?(\w+)(?:[:|][^{}]*)?\}").unwrap();
 
-fn read_os_release(key: &str, key_alt: Option<&str>) -> String {
-    std::fs::read_to_string("/etc/os-release")
-        .ok()
-        .and_then(|release| {
-            release.lines().find_map(|line| {
-                line.strip_prefix(key)
-                    .or_else(|| key_alt.and_then(|alt| line.strip_prefix(alt)))
-                    .map(|val| val.trim_matches('"').to_string())
-            })
-        })
-        .unwrap_or_else(|| "Failed to read /etc/os-release!".to_string())
+    re.captures_iter(format)
+        .map(|cap| cap[1].to_string())
+        .collect()
 }
 
-pub fn batt() -> Batt {
-    let level = match fs::read_to_string("/sys/class/power_supply/BAT0/capacity") {
-        Ok(l) => l.trim().to_string(),
-        Err(_) => String::from("Failed to read battery level!"),
-    };
-    let status = match fs::read_to_string("/sys/class/power_supply/BAT0/status") {
-        Ok(s) => s.trim().to_string(),
-        Err(_) => String::from("Failed to read battery status!"),
-    };
-
-    Batt { level, status }
+/// What a fetcher is asked for: the config, and which of its placeholders
+/// the format string actually uses (`None` for all of them).
+pub struct Context<'a> {
+    pub config: &'a Config,
+    used: Option<HashSet<String>>,
 }
 
-pub fn ram() -> Ram {
-    use std::collections::HashMap;
-
-    let memfile = fs::read_to_string("/proc/meminfo").unwrap();
-
-    let mut values = HashMap::new();
-    for line in memfile.lines() {
-        if let Some((key, val)) = line.split_once(':') {
-            let num = val
-                .split_whitespace()
-                .next()
-                .unwrap()
-                .parse::<u64>()
-                .unwrap();
-            values.insert(key.to_string(), num);
+impl<'a> Context<'a> {
+    pub fn new(config: &'a Config, key: &str) -> Self {
+        let used = config
+            .format
+            .get(key)
+            .map(crate::format::used_placeholders)
+            .unwrap_or_default();
+
+        Context {
+            config,
+            used: Some(used),
         }
     }
 
-    let total = values["MemTotal"] / 1024;
-    let free = values["MemAvailable"] / 1024;
-    let used = total - free;
-    let used_percentage = (used as f32 / total as f32 * 100.0).floor();
-    let free_percentage = (free as f32 / total as f32 * 100.0).floor();
-
-    let total = total.to_string();
-    let used = used.to_string();
-    let free = free.to_string();
-    let used_percentage = used_percentage.to_string();
-    let free_percentage = free_percentage.to_string();
-
-    let swap_total = values["SwapTotal"] / 1024;
-    let swap_free = values["SwapFree"] / 1024;
-    let swap_used = swap_total - swap_free;
-    let swap_used_percentage = if swap_total > 0 {
-        (swap_used as f32 / swap_total as f32 * 100.0).floor()
-    } else {
-        0.0
-    };
-    let swap_free_percentage = if swap_total > 0 {
-        (swap_free as f32 / swap_total as f32 * 100.0).floor()
-    } else {
-        0.0
-    };
-
-    let swap_total = swap_total.to_string();
-    let swap_used = swap_used.to_string();
-    let swap_free = swap_free.to_string();
-    let swap_used_percentage = swap_used_percentage.to_string();
-    let swap_free_percentage = swap_free_percentage.to_string();
-
-    Ram {
-        total,
-        used,
-        free,
-        used_percentage,
-        free_percentage,
-        swap_total,
-        swap_used,
-        swap_free,
-        swap_used_percentage,
-        swap_free_percentage,
+    /// A context asking for every placeholder, regardless of the format string.
+    pub fn all(config: &'a Config) -> Self {
+        Context { config, used: None }
     }
-}
 
-pub fn swap() -> Swap {
-    let total = String::from("8");
-    let used = String::from("4");
-    let free = String::from("4");
-    let free_percentage = String::from("50");
-    let used_percentage = String::from("50");
-
-    Swap {
-        total,
-        used,
-        free,
-        free_percentage,
-        used_percentage,
+    /// Whether `placeholder` is used, so expensive lookups can be skipped.
+    pub fn wants(&self, placeholder: &str) -> bool {
+        self.used
+            .as_ref()
+            .is_none_or(|used| used.contains(placeholder))
     }
 }
 
-pub fn uptime() -> Uptime {
-    let contents = fs::read_to_string("/proc/uptime").unwrap();
+/// A config key that can be listed in `keys`.
+pub trait Fetcher: Sync {
+    fn name(&self) -> &'static str;
 
-    let uptime: i32 = contents.split('.').next().unwrap().parse().unwrap();
+    /// Every placeholder the fetcher may fill in.
+    fn placeholders(&self) -> &'static [&'static str];
 
-    let d = uptime / 86400;
-    let h = (uptime % 86400) / 3600;
-    let m = (uptime % 3600) / 60;
-    let s = uptime % 60;
+    /// Fetches the values for this key, one `Fields` per output line.
+    /// Placeholders that could not be fetched are left out and shown as
+    /// unknown; an error is only returned if nothing could be fetched.
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError>;
 
-    Uptime { d, h, m, s }
-}
-
-pub fn lifetime() -> Lifetime {
-    use std::time::{Duration, SystemTime};
-
-    let now = SystemTime::now();
-    let mut oldest_time = now;
-
-    let path = Path::new("/");
-    if path.exists()
-        && let Ok(metadata) = fs::metadata(path)
-        && let Ok(created) = metadata.created()
-    {
-        oldest_time = created;
+    /// Whether the key lists several items (one line each) rather than
+    /// describing a single thing.
+    fn is_list(&self) -> bool {
+        false
     }
+}
 
-    let duration = now
-        .duration_since(oldest_time)
-        .unwrap_or(Duration::from_secs(0));
-
-    let y = duration.as_secs() as i32 / 31536000;
-    let mo = (duration.as_secs() as i32 % 31536000) / 2628000;
-    let d = (duration.as_secs() as i32 % 2628000) / 86400;
-    let h = (duration.as_secs() as i32 % 86400) / 3600;
-    let m = (duration.as_secs() as i32 % 3600) / 60;
-    let s = duration.as_secs() as i32 % 60;
+static REGISTRY: &[&dyn Fetcher] = &[
+    &batt::Batt,
+    &ram::Ram,
+    &swap::Swap,
+    &uptime::Uptime,
+    &lifetime::Lifetime,
+    &cpu::Cpu,
+    &gpu::Gpu,
+    &disk::Disk,
+    &net::Net,
+    &display::Display,
+    &de::De,
+    &wm::Wm,
+    &kernel::Kernel,
+    &distro::Distro,
+    &username::Username,
+    &hostname::Hostname,
+    &shell::Shell,
+    &terminal::Terminal,
+    &pkgs::Pkgs,
+];
+
+pub fn get(name: &str) -> Option<&'static dyn Fetcher> {
+    REGISTRY.iter().find(|f| f.name() == name).copied()
+}
 
-    Lifetime { y, mo, d, h, m, s }
+type FetchResult = Result<Vec<Fields>, FetchError>;
+
+/// Runs each fetcher at most once per run, however often its key is listed.
+/// Fetchers run on their own threads, so a slow one only delays its own line,
+/// and only up to its timeout.
+pub struct Cache {
+    config: Arc<Config>,
+    all_fields: bool,
+    started: Instant,
+    pending: HashMap<&'static str, mpsc::Receiver<FetchResult>>,
+    // `None` once a fetcher has timed out.
+    results: HashMap<&'static str, Option<FetchResult>>,
 }
 
-pub fn de() -> De {
-    let de = env::var("XDG_CURRENT_DESKTOP").unwrap_or(String::from(
-        "Failed to get $XDG_CURRENT_DESKTOP env variable!",
-    ));
+impl Cache {
+    pub fn new(config: Arc<Config>) -> Self {
+        Cache {
+            config,
+            all_fields: false,
+            started: Instant::now(),
+            pending: HashMap::new(),
+            results: HashMap::new(),
+        }
+    }
 
-    De { de }
-}
+    /// Fetch every placeholder of each key, not just those in its format string.
+    pub fn all_fields(mut self) -> Self {
+        self.all_fields = true;
+        self
+    }
 
-pub fn kernel() -> Kernel {
-    let command = Command::new("uname").arg("-r").output().unwrap();
-    let kernel = String::from_utf8(command.stdout)
-        .unwrap()
-        .trim()
-        .to_string();
+    /// Starts `fetcher` in the background, unless it has already been started.
+    pub fn spawn(&mut self, fetcher: &'static dyn Fetcher) {
+        let name = fetcher.name();
+        if self.pending.contains_key(name) || self.results.contains_key(name) {
+            return;
+        }
 
-    Kernel { kernel }
-}
+        let (tx, rx) = mpsc::channel();
+        let config = Arc::clone(&self.config);
+        let all_fields = self.all_fields;
+        thread::spawn(move || {
+            let ctx = if all_fields {
+                Context::all(&config)
+            } else {
+                Context::new(&config, name)
+            };
+            // The receiver is gone if the fetch timed out, nothing to do then.
+            let _ = tx.send(fetcher.fetch(&ctx));
+        });
 
-pub fn distro() -> Distro {
-    let distro = read_os_release("PRETTY_NAME=", Some("NAME="));
+        self.pending.insert(name, rx);
+    }
 
-    let version = read_os_release("BUILD_ID=", None);
+    fn timeout(&self, name: &str) -> Option<Duration> {
+        self.config
+            .timeouts
+            .as_ref()
+            .and_then(|timeouts| timeouts.get(name))
+            .or(self.config.timeout_ms.as_ref())
+            .map(|ms| Duration::from_millis(*ms))
+    }
 
-    let command = Command::new("uname").arg("-m").output().unwrap();
-    let arch = String::from_utf8(command.stdout)
-        .unwrap()
-        .trim()
-        .to_string();
+    /// Waits for the result of `fetcher`, returning `None` if it did not
+    /// finish within its timeout (counted from when the run started).
+    pub fn get(&mut self, fetcher: &'static dyn Fetcher) -> Option<&FetchResult> {
+        let name = fetcher.name();
+        self.spawn(fetcher);
+
+        if let Some(rx) = self.pending.remove(name) {
+            let result = match self.timeout(name) {
+                Some(timeout) => {
+                    let left = timeout.saturating_sub(self.started.elapsed());
+                    rx.recv_timeout(left).ok()
+                }
+                None => rx.recv().ok(),
+            };
+            self.results.insert(name, result);
+        }
 
-    Distro {
-        distro,
-        arch,
-        version,
+        self.results.get(name).and_then(|result| result.as_ref())
     }
 }
 
-pub fn username() -> Username {
-    let user = env::var("USER").unwrap_or(String::from("Failed to get $USER env variable!"));
-    let host = hostname::get().map_or(String::from("Failed to get hostname!"), |name| {
-        name.to_string_lossy().to_string()
-    });
+fn read(path: impl AsRef<Path>) -> Result<String, FetchError> {
+    fs::read_to_string(path.as_ref()).map_err(|source| FetchError::Read {
+        path: path.as_ref().display().to_string(),
+        source,
+    })
+}
 
-    Username { user, host }
+fn run(program: &str, args: &[&str]) -> Result<String, FetchError> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .map(|out| String::from_utf8_lossy(&out.stdout).to_string())
+        .map_err(|source| FetchError::Command {
+            program: program.to_string(),
+            source,
+        })
 }
 
-pub fn hostname() -> Hostname {
-    let host = hostname::get().map_or(String::from("Failed to get hostname!"), |name| {
-        name.to_string_lossy().to_string()
-    });
+pub fn read_os_release(key: &str, key_alt: Option<&str>) -> Result<String, FetchError> {
+    let release = read("/etc/os-release")?;
 
-    Hostname { host }
+    release
+        .lines()
+        .find_map(|line| {
+            line.strip_prefix(key)
+                .or_else(|| key_alt.and_then(|alt| line.strip_prefix(alt)))
+                .map(|val| val.trim_matches('"').to_string())
+        })
+        .ok_or(FetchError::Parse {
+            path: String::from("/etc/os-release"),
+            what: "release info",
+        })
 }
 
-pub fn shell() -> Shell {
-    let shell = env::var("SHELL")
-        .map_or(String::from("Couldn't get SHELL env variable!"), |name| {
-            name.split('/').next_back().unwrap().to_string()
-        });
+/// The parent pid and name of a process, from /proc/<pid>/stat.
+fn process_info(pid: u32) -> Option<(u32, String)> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
 
-    Shell { shell }
+    // The name is in parentheses, and may itself contain spaces and parentheses.
+    let (start, end) = (stat.find('(')?, stat.rfind(')')?);
+    let name = stat.get(start + 1..end)?.to_string();
+    let ppid = stat[end + 1..].split_whitespace().nth(1)?.parse().ok()?;
+
+    Some((ppid, name))
 }
 
-pub fn pkgs() -> Pkgs {
-    let distro_id = read_os_release("ID_LIKE=", Some("ID="));
-
-    let manager = distro_id
-    .as_str()
-    .split(' ')
-    .find_map(|distro| match distro {
-        "arch" => Some(String::from("pacman")),
-        "artix" => Some(String::from("pacman")),
-        "debian" => Some(String::from("dpkg")),
-        "void" => Some(String::from("xbps")),
-        "rhel" => Some(String::from("rpm")),
-        "gentoo" => Some(String::from("portage")),
-        _ => None,
-    })
-    .unwrap_or_else(|| {
-        format!(
-            "Failed to identify package manager: {distro_id}, either failed to read /etc/os-release or distro is not supported!"
-        )
-    });
-
-    let pm_command = match manager.as_str() {
-        "pacman" => Command::new("pacman")
-            .arg("-Q")
-            .output()
-            .expect("Failed to run package manager command!"),
-        "dpkg" => Command::new("dpkg")
-            .arg("-l")
-            .output()
-            .expect("Failed to run package manager command!"),
-        "xbps" => Command::new("xbps-query")
-            .arg("-l")
-            .output()
-            .expect("Failed to run package manager command!"),
-        "rpm" => Command::new("rpm")
-            .arg("-qa")
-            .output()
-            .expect("Failed to run package manager command!"),
-        "portage" => {
-            use std::os::unix::process::ExitStatusExt;
-
-            let mut count = 0;
-            if let Ok(categories) = fs::read_dir("/var/db/pkg") {
-                for cat in categories.flatten() {
-                    if let Ok(pkgs) = fs::read_dir(cat.path()) {
-                        count += pkgs.flatten().count();
-                    }
-                }
-            }
+fn percentage(part: u64, total: u64) -> Value {
+    if total > 0 {
+        Value::Int((part as f64 / total as f64 * 100.0).floor() as i64)
+    } else {
+        Value::Int(0)
+    }
+}
 
-            std::process::Output {
-                status: std::process::ExitStatus::from_raw(0),
-                stdout: count.to_string().into_bytes(),
-                stderr: Vec::new(),
+/// Folds larger time units into smaller ones when the larger unit is not used
+/// in the format string, e.g. `{h}` alone shows total hours instead of hours
+/// past the last full day.
+fn carry_time_units(ctx: &Context, fields: &mut Fields) {
+    let units = [
+        ("y", "mo", 12),
+        ("mo", "d", 30),
+        ("d", "h", 24),
+        ("h", "m", 60),
+        ("m", "s", 60),
+    ];
+
+    for (larger, smaller, factor) in units {
+        if !ctx.wants(larger) && ctx.wants(smaller) {
+            let carried = match fields.insert(larger, Value::Int(0)) {
+                Some(Value::Int(n)) => n,
+                _ => 0,
+            };
+            if let Some(Value::Int(n)) = fields.get_mut(smaller) {
+                *n += carried * factor;
             }
         }
-        _ => {
-            eprintln!("Error in pkgs key:");
-            eprintln!("Package manager/distro is not supported!");
-            eprintln!("exiting...");
-            std::process::exit(1);
-        }
-    };
-
-    let native = String::from_utf8_lossy(&pm_command.stdout)
-        .lines()
-        .count()
-        .saturating_sub(1)
-        .to_string();
-
-    let flatpak = {
-        Command::new("flatpak")
-            .arg("list")
-            .arg("--app")
-            .output()
-            .map_or(
-                "Failed to determine flatpak package count! (is flatpak available?)".to_string(),
-                |out| {
-                    String::from_utf8_lossy(&out.stdout)
-                        .lines()
-                        .count()
-                        .to_string()
-                },
-            )
-    };
-
-    let snap = {
-        Command::new("snap").arg("list").output().map_or(
-            "Failed to determine snap package count! (is snap available?)".to_string(),
-            |out| {
-                String::from_utf8_lossy(&out.stdout)
-                    .lines()
-                    .count()
-                    .to_string()
-            },
-        )
-    };
-
-    Pkgs {
-        native,
-        flatpak,
-        snap,
-        manager,
     }
 }
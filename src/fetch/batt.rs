@@ -0,0 +1,221 @@
+use super::{Context, FetchError, Fetcher, Fields, Value};
+use std::fs;
+use std::path::Path;
+
+pub struct Batt;
+
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+
+// Energies are in µWh and power in µW. Batteries that only report charge
+// (µAh) and current (µA) are converted using their voltage.
+struct Battery {
+    name: String,
+    status: String,
+    capacity: Option<i64>,
+    energy_now: Option<f64>,
+    energy_full: Option<f64>,
+    energy_full_design: Option<f64>,
+    power: Option<f64>,
+    cycles: Option<i64>,
+}
+
+fn read_battery(dir: &Path) -> Battery {
+    let read = |file: &str| {
+        fs::read_to_string(dir.join(file))
+            .map(|val| val.trim().to_string())
+            .ok()
+    };
+    let number = |file: &str| read(file).and_then(|val| val.parse::<f64>().ok());
+
+    let voltage = number("voltage_now").or_else(|| number("voltage_min_design"));
+    let energy = |which: &str| {
+        number(&format!("energy_{which}"))
+            .or_else(|| Some(number(&format!("charge_{which}"))? * voltage? / 1e6))
+    };
+
+    Battery {
+        name: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        status: read("status").unwrap_or_default(),
+        capacity: read("capacity").and_then(|val| val.parse().ok()),
+        energy_now: energy("now"),
+        energy_full: energy("full"),
+        energy_full_design: energy("full_design"),
+        // Some drivers report a negative current while discharging.
+        power: number("power_now")
+            .or_else(|| Some(number("current_now")? * voltage? / 1e6))
+            .map(f64::abs),
+        // Many batteries report 0 when they do not count cycles.
+        cycles: read("cycle_count")
+            .and_then(|val| val.parse().ok())
+            .filter(|cycles| *cycles > 0),
+    }
+}
+
+/// All batteries as one: energies and power are summed, and the status is
+/// charging or discharging if any battery is.
+fn combine(batteries: &[Battery]) -> Battery {
+    let sum = |value: fn(&Battery) -> Option<f64>| batteries.iter().map(value).sum::<Option<f64>>();
+
+    let status = ["Charging", "Discharging"]
+        .into_iter()
+        .find(|status| batteries.iter().any(|b| b.status == *status))
+        .map(String::from)
+        .unwrap_or_else(|| batteries[0].status.clone());
+
+    let energy_now = sum(|b| b.energy_now);
+    let energy_full = sum(|b| b.energy_full);
+
+    // Weighted by energy if possible, otherwise the average.
+    let capacities: Vec<i64> = batteries.iter().filter_map(|b| b.capacity).collect();
+    let capacity = match (energy_now, energy_full) {
+        (Some(now), Some(full)) if full > 0.0 => Some((now / full * 100.0).round() as i64),
+        _ => (!capacities.is_empty())
+            .then(|| capacities.iter().sum::<i64>() / capacities.len() as i64),
+    };
+
+    Battery {
+        name: batteries
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect::<Vec<_>>()
+            .join("+"),
+        status,
+        capacity,
+        energy_now,
+        energy_full,
+        energy_full_design: sum(|b| b.energy_full_design),
+        power: sum(|b| b.power),
+        cycles: None,
+    }
+}
+
+fn time_remaining(battery: &Battery) -> Option<String> {
+    let power = battery.power.filter(|power| *power > 0.0)?;
+    let now = battery.energy_now?;
+
+    let hours = match battery.status.as_str() {
+        "Discharging" => now / power,
+        "Charging" => (battery.energy_full? - now).max(0.0) / power,
+        _ => return None,
+    };
+
+    let minutes = (hours * 60.0).round() as i64;
+    Some(format!("{}h {:02}m", minutes / 60, minutes % 60))
+}
+
+/// Whether any mains adapter is plugged in, if there is one at all.
+fn ac_state() -> Option<&'static str> {
+    let mut online = None;
+
+    for entry in fs::read_dir(POWER_SUPPLY).ok()?.flatten() {
+        let path = entry.path();
+        if fs::read_to_string(path.join("type")).is_ok_and(|kind| kind.trim() == "Mains") {
+            let plugged =
+                fs::read_to_string(path.join("online")).is_ok_and(|val| val.trim() == "1");
+            online = Some(online.unwrap_or(false) || plugged);
+        }
+    }
+
+    online.map(|online| if online { "online" } else { "offline" })
+}
+
+fn fields(battery: Battery, ac: Option<&str>) -> Fields {
+    let mut fields = Fields::new();
+
+    let level = battery.capacity.or_else(|| {
+        let full = battery.energy_full.filter(|full| *full > 0.0)?;
+        Some((battery.energy_now? / full * 100.0).round() as i64)
+    });
+    if let Some(level) = level {
+        fields.insert("level", Value::Int(level));
+    }
+    if let (Some(full), Some(design)) = (battery.energy_full, battery.energy_full_design)
+        && design > 0.0
+    {
+        fields.insert("health", Value::Int((full / design * 100.0).round() as i64));
+    }
+    if let Some(time) = time_remaining(&battery) {
+        fields.insert("time_remaining", Value::Text(time));
+    }
+    if let Some(power) = battery.power {
+        fields.insert("power_w", Value::Float(power / 1e6));
+    }
+    if let Some(cycles) = battery.cycles {
+        fields.insert("cycles", Value::Int(cycles));
+    }
+    if let Some(ac) = ac {
+        fields.insert("ac", Value::from(ac));
+    }
+    fields.insert("status", Value::Text(battery.status));
+    fields.insert("name", Value::Text(battery.name));
+
+    fields
+}
+
+impl Fetcher for Batt {
+    fn name(&self) -> &'static str {
+        "batt"
+    }
+
+    fn is_list(&self) -> bool {
+        true
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &[
+            "name",
+            "level",
+            "status",
+            "time_remaining",
+            "health",
+            "power_w",
+            "cycles",
+            "ac",
+        ]
+    }
+
+    /// `[batt] show` is either "each" (a line per battery) or "total" (one
+    /// line for all of them).
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let show = ctx
+            .config
+            .batt
+            .as_ref()
+            .and_then(|batt| batt.show.as_deref())
+            .unwrap_or("each");
+
+        let mut batteries: Vec<Battery> = fs::read_dir(POWER_SUPPLY)
+            .map_err(|source| FetchError::Read {
+                path: POWER_SUPPLY.to_string(),
+                source,
+            })?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let read = |file: &str| fs::read_to_string(path.join(file)).unwrap_or_default();
+                // Devices like mice and controllers have a battery too.
+                read("type").trim() == "Battery" && read("scope").trim() != "Device"
+            })
+            .map(|path| read_battery(&path))
+            .collect();
+        batteries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if batteries.is_empty() {
+            return Err(FetchError::Unsupported(String::from("No battery found!")));
+        }
+
+        let ac = if ctx.wants("ac") { ac_state() } else { None };
+
+        if show == "total" {
+            Ok(vec![fields(combine(&batteries), ac)])
+        } else {
+            Ok(batteries
+                .into_iter()
+                .map(|battery| fields(battery, ac))
+                .collect())
+        }
+    }
+}
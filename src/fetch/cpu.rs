@@ -0,0 +1,122 @@
+use super::{Context, FetchError, Fetcher, Fields, Value, read};
+use std::collections::HashSet;
+use std::fs;
+
+pub struct Cpu;
+
+// Instruction set extensions worth showing, in the order they are listed.
+const NOTABLE_FLAGS: [&str; 12] = [
+    "sse4_2", "avx", "avx2", "avx512f", "fma", "aes", "sha_ni", "vaes", "amx_tile", "neon",
+    "asimd", "sve",
+];
+
+impl Fetcher for Cpu {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &[
+            "model",
+            "vendor",
+            "cores",
+            "threads",
+            "freq_cur",
+            "freq_max",
+            "arch_flags",
+        ]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let cpuinfo = read("/proc/cpuinfo")?;
+
+        let field = |names: &[&str]| {
+            cpuinfo.lines().find_map(|line| {
+                let (key, val) = line.split_once(':')?;
+                names.contains(&key.trim()).then(|| val.trim().to_string())
+            })
+        };
+
+        let mut threads = 0;
+        let mut core_ids = HashSet::new();
+        let mut physical_id = String::new();
+        for line in cpuinfo.lines() {
+            if let Some((key, val)) = line.split_once(':') {
+                match key.trim() {
+                    "processor" => threads += 1,
+                    "physical id" => physical_id = val.trim().to_string(),
+                    "core id" => {
+                        core_ids.insert((physical_id.clone(), val.trim().to_string()));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let cores = if core_ids.is_empty() {
+            threads
+        } else {
+            core_ids.len()
+        };
+
+        // Frequencies are reported in kHz by cpufreq, shown in MHz.
+        let mut freq_cur = 0;
+        let mut freq_max = 0;
+        let wants_freq = ctx.wants("freq_cur") || ctx.wants("freq_max");
+        if wants_freq && let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !name
+                    .strip_prefix("cpu")
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+                {
+                    continue;
+                }
+
+                let read_khz = |file: &str| {
+                    fs::read_to_string(entry.path().join("cpufreq").join(file))
+                        .ok()
+                        .and_then(|f| f.trim().parse::<i64>().ok())
+                        .unwrap_or(0)
+                };
+                freq_cur = freq_cur.max(read_khz("scaling_cur_freq") / 1000);
+                freq_max = freq_max.max(read_khz("cpuinfo_max_freq") / 1000);
+            }
+        }
+
+        if wants_freq && freq_cur == 0 {
+            freq_cur = cpuinfo
+                .lines()
+                .filter_map(|line| line.strip_prefix("cpu MHz"))
+                .filter_map(|val| val.trim_start_matches([' ', '\t', ':']).parse::<f64>().ok())
+                .fold(0.0, f64::max) as i64;
+        }
+
+        let flags = field(&["flags", "Features"]).unwrap_or_default();
+        let flags: HashSet<&str> = flags.split_whitespace().collect();
+        let arch_flags = NOTABLE_FLAGS
+            .iter()
+            .filter(|flag| flags.contains(*flag))
+            .copied()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut fields = Fields::new();
+        if let Some(model) = field(&["model name", "Model", "Hardware", "cpu model"]) {
+            fields.insert("model", Value::Text(model));
+        }
+        if let Some(vendor) = field(&["vendor_id", "CPU implementer", "vendor"]) {
+            fields.insert("vendor", Value::Text(vendor));
+        }
+        fields.insert("cores", Value::Int(cores as i64));
+        fields.insert("threads", Value::Int(threads as i64));
+        if freq_cur > 0 {
+            fields.insert("freq_cur", Value::Int(freq_cur));
+        }
+        if freq_max > 0 {
+            fields.insert("freq_max", Value::Int(freq_max));
+        }
+        fields.insert("arch_flags", Value::Text(arch_flags));
+
+        Ok(vec![fields])
+    }
+}
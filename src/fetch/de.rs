@@ -0,0 +1,24 @@
+use super::{Context, FetchError, Fetcher, Fields, Value};
+use std::env;
+
+pub struct De;
+
+impl Fetcher for De {
+    fn name(&self) -> &'static str {
+        "de"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["de"]
+    }
+
+    fn fetch(&self, _ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let de =
+            env::var("XDG_CURRENT_DESKTOP").map_err(|_| FetchError::Env("XDG_CURRENT_DESKTOP"))?;
+
+        let mut fields = Fields::new();
+        fields.insert("de", Value::Text(de));
+
+        Ok(vec![fields])
+    }
+}
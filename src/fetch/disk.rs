@@ -0,0 +1,109 @@
+use super::{Context, FetchError, Fetcher, Fields, Unit, Value, percentage, read};
+use std::collections::HashSet;
+
+pub struct Disk;
+
+fn statvfs(path: &str) -> Option<libc::statvfs> {
+    let path = std::ffi::CString::new(path).ok()?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `path` is a valid NUL-terminated string and `stat` is only read on success.
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } == 0 {
+        Some(unsafe { stat.assume_init() })
+    } else {
+        None
+    }
+}
+
+impl Fetcher for Disk {
+    fn name(&self) -> &'static str {
+        "disk"
+    }
+
+    fn is_list(&self) -> bool {
+        true
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &[
+            "mount",
+            "fstype",
+            "device",
+            "total",
+            "used",
+            "free",
+            "used_percentage",
+        ]
+    }
+
+    /// With `[disk] mounts` set, only those mount points are listed (in the
+    /// given order), otherwise every mount backed by a block device.
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let mounts = ctx
+            .config
+            .disk
+            .as_ref()
+            .and_then(|disk| disk.mounts.as_deref());
+        let mountinfo = read("/proc/self/mountinfo")?;
+
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+        for line in mountinfo.lines() {
+            let Some((fields, fs_fields)) = line.split_once(" - ") else {
+                continue;
+            };
+            let fields: Vec<&str> = fields.split_whitespace().collect();
+            let fs_fields: Vec<&str> = fs_fields.split_whitespace().collect();
+            if fields.len() < 5 || fs_fields.len() < 2 {
+                continue;
+            }
+
+            let mount = fields[4].replace("\\040", " ");
+            let (fstype, device) = (fs_fields[0], fs_fields[1]);
+
+            let wanted = match mounts {
+                Some(mounts) => mounts.contains(&mount),
+                None => {
+                    (device.starts_with("/dev/") && fstype != "squashfs")
+                        || matches!(fstype, "zfs" | "nfs" | "nfs4" | "cifs" | "fuseblk")
+                }
+            };
+
+            // Bind mounts of the same filesystem root would otherwise be listed twice.
+            if wanted && seen.insert((fields[2], fields[3])) {
+                entries.push((mount, fstype, device));
+            }
+        }
+
+        if let Some(mounts) = mounts {
+            entries.sort_by_key(|(mount, _, _)| mounts.iter().position(|m| m == mount));
+        }
+
+        Ok(entries
+            .into_iter()
+            .map(|(mount, fstype, device)| {
+                let mut fields = Fields::new();
+
+                // The statvfs field types are only 64-bit on some targets.
+                #[allow(clippy::unnecessary_cast)]
+                if let Some(stat) = statvfs(&mount) {
+                    let block = stat.f_frsize as u64;
+                    let total = stat.f_blocks as u64 * block;
+                    let used = (stat.f_blocks - stat.f_bfree) as u64 * block;
+                    let free = stat.f_bavail as u64 * block;
+
+                    fields.insert("total", Value::Size(total, Unit::GiB));
+                    fields.insert("used", Value::Size(used, Unit::GiB));
+                    fields.insert("free", Value::Size(free, Unit::GiB));
+                    // Same as df: reserved blocks count as neither used nor available.
+                    fields.insert("used_percentage", percentage(used, used + free));
+                }
+
+                fields.insert("mount", Value::Text(mount));
+                fields.insert("fstype", Value::from(fstype));
+                fields.insert("device", Value::from(device));
+                fields
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,199 @@
+use super::{Context, FetchError, Fetcher, Fields, Value};
+use std::fs;
+use std::path::Path;
+
+pub struct Display;
+
+const DRM: &str = "/sys/class/drm";
+
+/// Resolution and refresh rate of the preferred mode, from the first
+/// detailed timing descriptor of the EDID.
+fn edid_mode(edid: &[u8]) -> Option<((u32, u32), f64)> {
+    let dtd = edid.get(54..72)?;
+    let clock = u16::from_le_bytes([dtd[0], dtd[1]]) as f64 * 10_000.0;
+    if clock == 0.0 {
+        return None;
+    }
+
+    let h_active = dtd[2] as u32 | (dtd[4] as u32 & 0xf0) << 4;
+    let h_blank = dtd[3] as u32 | (dtd[4] as u32 & 0x0f) << 8;
+    let v_active = dtd[5] as u32 | (dtd[7] as u32 & 0xf0) << 4;
+    let v_blank = dtd[6] as u32 | (dtd[7] as u32 & 0x0f) << 8;
+
+    let refresh = clock / ((h_active + h_blank) as f64 * (v_active + v_blank) as f64);
+    Some(((h_active, v_active), refresh))
+}
+
+/// Only known from the toolkit scaling variables, as the compositor is not
+/// asked.
+fn scale() -> Option<String> {
+    ["GDK_SCALE", "QT_SCALE_FACTOR"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|scale| !scale.is_empty()))
+}
+
+fn output(path: &Path, scale: Option<&str>) -> Option<Fields> {
+    let read = |file: &str| fs::read_to_string(path.join(file)).ok();
+    if read("status")?.trim() != "connected" {
+        return None;
+    }
+
+    // Connectors are named like card0-HDMI-A-1.
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let output = name
+        .split_once('-')
+        .map_or(name.as_str(), |(_, output)| output);
+
+    let mut fields = Fields::new();
+    fields.insert("output", Value::from(output));
+
+    let edid = fs::read(path.join("edid"))
+        .ok()
+        .and_then(|edid| edid_mode(&edid));
+    // The first mode is the preferred one.
+    let mode = read("modes").and_then(|modes| modes.lines().next().map(String::from));
+    match (edid, mode) {
+        (Some(((width, height), refresh)), ref mode)
+            if mode
+                .as_ref()
+                .is_none_or(|mode| *mode == format!("{width}x{height}")) =>
+        {
+            fields.insert("resolution", Value::Text(format!("{width}x{height}")));
+            fields.insert("refresh", Value::Float(refresh));
+        }
+        (_, Some(mode)) => {
+            fields.insert("resolution", Value::Text(mode));
+        }
+        _ => {}
+    }
+    if let Some(scale) = scale {
+        fields.insert("scale", Value::from(scale));
+    }
+
+    Some(fields)
+}
+
+/// All outputs on one line, with the values of each placeholder joined.
+/// A placeholder is left out unless every output has it, so the joined
+/// values always line up with the outputs.
+fn combine(outputs: Vec<Fields>) -> Fields {
+    let mut combined = Fields::new();
+
+    for name in Display.placeholders() {
+        let values: Option<Vec<String>> = outputs
+            .iter()
+            .map(|fields| fields.get(name).map(Value::to_string))
+            .collect();
+        if let Some(values) = values {
+            combined.insert(name, Value::Text(values.join(", ")));
+        }
+    }
+
+    combined
+}
+
+impl Fetcher for Display {
+    fn name(&self) -> &'static str {
+        "display"
+    }
+
+    fn is_list(&self) -> bool {
+        true
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["output", "resolution", "refresh", "scale"]
+    }
+
+    /// Reads the connectors in /sys/class/drm, so it works without a running
+    /// X or Wayland session. `[display] show` is either "each" (a line per
+    /// output) or "combined".
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let show = ctx
+            .config
+            .display
+            .as_ref()
+            .and_then(|display| display.show.as_deref())
+            .unwrap_or("each");
+
+        let mut connectors: Vec<_> = fs::read_dir(DRM)
+            .map_err(|source| FetchError::Read {
+                path: DRM.to_string(),
+                source,
+            })?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .is_some_and(|name| name.to_string_lossy().contains('-'))
+            })
+            .collect();
+        connectors.sort();
+
+        let scale = scale();
+        let outputs: Vec<Fields> = connectors
+            .iter()
+            .filter_map(|path| output(path, scale.as_deref()))
+            .collect();
+
+        if outputs.is_empty() {
+            return Err(FetchError::Unsupported(String::from(
+                "No connected display found!",
+            )));
+        }
+
+        if show == "combined" {
+            Ok(vec![combine(outputs)])
+        } else {
+            Ok(outputs)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The preferred mode of a common 1920x1080 monitor: 148.5 MHz pixel
+    // clock, 280 columns and 45 lines of blanking.
+    const DTD_1080P: [u8; 18] = [
+        0x02, 0x3a, 0x80, 0x18, 0x71, 0x38, 0x2d, 0x40, 0x58, 0x2c, 0x45, 0x00, 0x56, 0x50, 0x21,
+        0x00, 0x00, 0x1e,
+    ];
+
+    fn edid(dtd: &[u8]) -> Vec<u8> {
+        let mut edid = vec![0; 128];
+        edid[54..54 + dtd.len()].copy_from_slice(dtd);
+        edid
+    }
+
+    #[test]
+    fn edid_1080p() {
+        let ((width, height), refresh) = edid_mode(&edid(&DTD_1080P)).unwrap();
+        assert_eq!((width, height), (1920, 1080));
+        assert!((refresh - 60.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn edid_without_mode() {
+        // A pixel clock of 0 marks a display descriptor, not a timing.
+        assert!(edid_mode(&edid(&[0; 18])).is_none());
+        assert!(edid_mode(&DTD_1080P).is_none());
+    }
+
+    #[test]
+    fn combine_outputs() {
+        let mut laptop = Fields::new();
+        laptop.insert("output", Value::from("eDP-1"));
+        laptop.insert("refresh", Value::Float(60.0));
+        let mut monitor = Fields::new();
+        monitor.insert("output", Value::from("HDMI-A-1"));
+
+        let combined = combine(vec![laptop, monitor]);
+        assert_eq!(
+            combined.get("output"),
+            Some(&Value::from("eDP-1, HDMI-A-1"))
+        );
+        assert_eq!(combined.get("refresh"), None);
+    }
+}
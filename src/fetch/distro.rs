@@ -0,0 +1,31 @@
+use super::{Context, FetchError, Fetcher, Fields, Value, read_os_release, run};
+
+pub struct Distro;
+
+impl Fetcher for Distro {
+    fn name(&self) -> &'static str {
+        "distro"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["distro", "arch", "version"]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let distro = read_os_release("PRETTY_NAME=", Some("NAME="))?;
+
+        let mut fields = Fields::new();
+        fields.insert("distro", Value::Text(distro));
+        if ctx.wants("arch")
+            && let Ok(arch) = run("uname", &["-m"])
+        {
+            fields.insert("arch", Value::from(arch.trim()));
+        }
+        // Rolling release distros usually only have a BUILD_ID.
+        if let Ok(version) = read_os_release("BUILD_ID=", None) {
+            fields.insert("version", Value::Text(version));
+        }
+
+        Ok(vec![fields])
+    }
+}
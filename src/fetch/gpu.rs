@@ -0,0 +1,217 @@
+use super::{Context, FetchError, Fetcher, Fields, Unit, Value};
+use std::collections::BTreeSet;
+use std::fs;
+
+pub struct Gpu;
+
+// Used when no system pci.ids is installed, so at least the vendor can be named.
+const PCI_VENDORS: [(&str, &str); 8] = [
+    ("8086", "Intel Corporation"),
+    ("1002", "Advanced Micro Devices, Inc. [AMD/ATI]"),
+    ("1022", "Advanced Micro Devices, Inc. [AMD]"),
+    ("10de", "NVIDIA Corporation"),
+    ("1af4", "Red Hat, Inc."),
+    ("1234", "QEMU"),
+    ("15ad", "VMware"),
+    ("80ee", "InnoTek Systemberatung GmbH"),
+];
+
+fn read_pci_ids() -> Option<String> {
+    [
+        "/usr/share/hwdata/pci.ids",
+        "/usr/share/misc/pci.ids",
+        "/usr/share/pci.ids",
+    ]
+    .iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+}
+
+/// Vendor and device name from the contents of pci.ids, or only the vendor
+/// from the built-in list if there is no pci.ids.
+fn pci_names(
+    ids: Option<&str>,
+    vendor_id: &str,
+    device_id: &str,
+) -> (Option<String>, Option<String>) {
+    if vendor_id.is_empty() {
+        return (None, None);
+    }
+
+    let Some(ids) = ids else {
+        let vendor = PCI_VENDORS
+            .iter()
+            .find(|(id, _)| *id == vendor_id)
+            .map(|(_, name)| name.to_string());
+        return (vendor, None);
+    };
+
+    let mut vendor = None;
+    for line in ids.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+
+        if !line.starts_with('\t') {
+            if vendor.is_some() {
+                break;
+            }
+            if let Some(name) = line.strip_prefix(vendor_id) {
+                vendor = Some(name.trim().to_string());
+            }
+        } else if vendor.is_some()
+            && let Some(name) = line
+                .strip_prefix('\t')
+                .and_then(|l| l.strip_prefix(device_id))
+        {
+            return (vendor, Some(name.trim().to_string()));
+        }
+    }
+
+    (vendor, None)
+}
+
+impl Fetcher for Gpu {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn is_list(&self) -> bool {
+        true
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["name", "vendor", "driver", "vram"]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let mut devices = BTreeSet::new();
+        let wants_names = ctx.wants("name") || ctx.wants("vendor");
+        // pci.ids is a large file, only read it when names are shown.
+        let ids = if wants_names { read_pci_ids() } else { None };
+
+        if let Ok(cards) = fs::read_dir("/sys/class/drm") {
+            for card in cards.flatten() {
+                let name = card.file_name().to_string_lossy().to_string();
+                if name.starts_with("card")
+                    && !name.contains('-')
+                    && let Ok(device) = fs::canonicalize(card.path().join("device"))
+                {
+                    devices.insert(device);
+                }
+            }
+        }
+
+        // Display controllers have PCI class 0x03xxxx.
+        if let Ok(pci) = fs::read_dir("/sys/bus/pci/devices") {
+            for device in pci.flatten() {
+                if fs::read_to_string(device.path().join("class"))
+                    .is_ok_and(|class| class.trim().starts_with("0x03"))
+                    && let Ok(device) = fs::canonicalize(device.path())
+                {
+                    devices.insert(device);
+                }
+            }
+        }
+
+        Ok(devices
+            .iter()
+            .map(|device| {
+                let read_id = |file: &str| {
+                    fs::read_to_string(device.join(file))
+                        .map(|id| id.trim().trim_start_matches("0x").to_lowercase())
+                        .unwrap_or_default()
+                };
+                let vendor_id = read_id("vendor");
+                let device_id = read_id("device");
+                let uevent = fs::read_to_string(device.join("uevent")).unwrap_or_default();
+                let uevent_value = |key: &str| {
+                    uevent.lines().find_map(|line| {
+                        line.strip_prefix(key)?.strip_prefix('=').map(String::from)
+                    })
+                };
+
+                let (vendor, name) = if wants_names {
+                    pci_names(ids.as_deref(), &vendor_id, &device_id)
+                } else {
+                    (None, None)
+                };
+                let driver = fs::read_link(device.join("driver"))
+                    .ok()
+                    .and_then(|driver| driver.file_name().map(|n| n.to_string_lossy().to_string()))
+                    .or_else(|| uevent_value("DRIVER"));
+
+                // Platform GPUs (common on ARM) have no PCI ids, but a device
+                // tree name like `arm,mali-bifrost`.
+                let name = name
+                    .or_else(|| {
+                        (!vendor_id.is_empty() && !device_id.is_empty())
+                            .then(|| format!("{vendor_id}:{device_id}"))
+                    })
+                    .or_else(|| uevent_value("OF_COMPATIBLE_0"))
+                    .or_else(|| driver.clone());
+
+                let mut fields = Fields::new();
+                if let Some(name) = name {
+                    fields.insert("name", Value::Text(name));
+                }
+                if let Some(vendor) = vendor {
+                    fields.insert("vendor", Value::Text(vendor));
+                }
+                if let Some(driver) = driver {
+                    fields.insert("driver", Value::Text(driver));
+                }
+                // Only amdgpu exposes VRAM size through sysfs, in bytes.
+                if let Some(vram) = fs::read_to_string(device.join("mem_info_vram_total"))
+                    .ok()
+                    .and_then(|vram| vram.trim().parse::<u64>().ok())
+                {
+                    fields.insert("vram", Value::Size(vram, Unit::MiB));
+                }
+
+                fields
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDS: &str = "\
+# comment
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t73bf  Navi 21 [Radeon RX 6800/6800 XT / 6900 XT]
+\t\t1002 0e3a  Radeon RX 6900 XT
+10de  NVIDIA Corporation
+\t2684  AD102 [GeForce RTX 4090]
+";
+
+    #[test]
+    fn names_from_pci_ids() {
+        assert_eq!(
+            pci_names(Some(IDS), "10de", "2684"),
+            (
+                Some(String::from("NVIDIA Corporation")),
+                Some(String::from("AD102 [GeForce RTX 4090]"))
+            )
+        );
+        assert_eq!(
+            pci_names(Some(IDS), "1002", "ffff"),
+            (
+                Some(String::from("Advanced Micro Devices, Inc. [AMD/ATI]")),
+                None
+            )
+        );
+        assert_eq!(pci_names(Some(IDS), "", ""), (None, None));
+    }
+
+    #[test]
+    fn vendor_without_pci_ids() {
+        assert_eq!(
+            pci_names(None, "8086", "46a6"),
+            (Some(String::from("Intel Corporation")), None)
+        );
+        assert_eq!(pci_names(None, "abcd", "0001"), (None, None));
+    }
+}
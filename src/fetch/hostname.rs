@@ -0,0 +1,25 @@
+use super::{Context, FetchError, Fetcher, Fields, Value};
+
+pub struct Hostname;
+
+impl Fetcher for Hostname {
+    fn name(&self) -> &'static str {
+        "hostname"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["host"]
+    }
+
+    fn fetch(&self, _ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let host = hostname::get().map_err(|source| FetchError::Read {
+            path: String::from("hostname"),
+            source,
+        })?;
+
+        let mut fields = Fields::new();
+        fields.insert("host", Value::Text(host.to_string_lossy().to_string()));
+
+        Ok(vec![fields])
+    }
+}
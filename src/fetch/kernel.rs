@@ -0,0 +1,22 @@
+use super::{Context, FetchError, Fetcher, Fields, Value, run};
+
+pub struct Kernel;
+
+impl Fetcher for Kernel {
+    fn name(&self) -> &'static str {
+        "kernel"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["kernel"]
+    }
+
+    fn fetch(&self, _ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let kernel = run("uname", &["-r"])?;
+
+        let mut fields = Fields::new();
+        fields.insert("kernel", Value::from(kernel.trim()));
+
+        Ok(vec![fields])
+    }
+}
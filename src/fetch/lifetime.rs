@@ -0,0 +1,45 @@
+use super::{Context, FetchError, Fetcher, Fields, Value, carry_time_units};
+use std::time::{Duration, SystemTime};
+use std::{fs, path::Path};
+
+pub struct Lifetime;
+
+impl Fetcher for Lifetime {
+    fn name(&self) -> &'static str {
+        "lifetime"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["y", "mo", "d", "h", "m", "s"]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let now = SystemTime::now();
+        let mut oldest_time = now;
+
+        let path = Path::new("/");
+        if path.exists()
+            && let Ok(metadata) = fs::metadata(path)
+            && let Ok(created) = metadata.created()
+        {
+            oldest_time = created;
+        }
+
+        let duration = now
+            .duration_since(oldest_time)
+            .unwrap_or(Duration::from_secs(0))
+            .as_secs() as i64;
+
+        let mut fields = Fields::new();
+        fields.insert("y", Value::Int(duration / 31536000));
+        fields.insert("mo", Value::Int((duration % 31536000) / 2628000));
+        fields.insert("d", Value::Int((duration % 2628000) / 86400));
+        fields.insert("h", Value::Int((duration % 86400) / 3600));
+        fields.insert("m", Value::Int((duration % 3600) / 60));
+        fields.insert("s", Value::Int(duration % 60));
+
+        carry_time_units(ctx, &mut fields);
+
+        Ok(vec![fields])
+    }
+}
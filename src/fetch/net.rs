@@ -0,0 +1,174 @@
+use super::{Context, FetchError, Fetcher, Fields, Value};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::process::Command;
+
+pub struct Net;
+
+fn default_route_iface() -> Option<String> {
+    let routes = fs::read_to_string("/proc/net/route").ok()?;
+
+    routes.lines().skip(1).find_map(|line| {
+        let mut cols = line.split_whitespace();
+        let iface = cols.next()?;
+        (cols.next()? == "00000000").then(|| iface.to_string())
+    })
+}
+
+fn iface_addrs() -> HashMap<String, (Vec<String>, Vec<String>)> {
+    let mut addrs: HashMap<String, (Vec<String>, Vec<String>)> = HashMap::new();
+    let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+
+    // SAFETY: getifaddrs allocates a linked list that is walked read-only and
+    // released with freeifaddrs before returning.
+    unsafe {
+        if libc::getifaddrs(&mut ifap) != 0 {
+            return addrs;
+        }
+
+        let mut ifa = ifap;
+        while let Some(entry) = ifa.as_ref() {
+            ifa = entry.ifa_next;
+            if entry.ifa_addr.is_null() {
+                continue;
+            }
+
+            let name = CStr::from_ptr(entry.ifa_name).to_string_lossy().to_string();
+            match i32::from((*entry.ifa_addr).sa_family) {
+                libc::AF_INET => {
+                    let addr = &*(entry.ifa_addr as *const libc::sockaddr_in);
+                    let ip = Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+                    addrs.entry(name).or_default().0.push(ip.to_string());
+                }
+                libc::AF_INET6 => {
+                    let addr = &*(entry.ifa_addr as *const libc::sockaddr_in6);
+                    let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+                    // Link-local addresses are present on every interface and not useful here.
+                    if ip.segments()[0] & 0xffc0 != 0xfe80 {
+                        addrs.entry(name).or_default().1.push(ip.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        libc::freeifaddrs(ifap);
+    }
+
+    addrs
+}
+
+fn ssid(iface: &str) -> Option<String> {
+    let wireless = fs::read_to_string("/proc/net/wireless").ok()?;
+    if !wireless
+        .lines()
+        .skip(2)
+        .any(|line| line.trim_start().starts_with(&format!("{iface}:")))
+    {
+        return None;
+    }
+
+    // The kernel does not expose the SSID through procfs, ask the wireless tools.
+    if let Ok(out) = Command::new("iwgetid").args(["-r", iface]).output() {
+        let ssid = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        if !ssid.is_empty() {
+            return Some(ssid);
+        }
+    }
+
+    let out = Command::new("iw")
+        .args(["dev", iface, "link"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("SSID: ").map(|s| s.to_string()))
+}
+
+impl Fetcher for Net {
+    fn name(&self) -> &'static str {
+        "net"
+    }
+
+    fn is_list(&self) -> bool {
+        true
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["iface", "ipv4", "ipv6", "mac", "state", "speed", "ssid"]
+    }
+
+    /// `[net] show` is either "default" (only the interface holding the
+    /// default route) or "all" (every non-loopback interface).
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let show = ctx
+            .config
+            .net
+            .as_ref()
+            .and_then(|net| net.show.as_deref())
+            .unwrap_or("default");
+
+        let ifaces: Vec<String> = if show == "all" {
+            let mut ifaces: Vec<String> = fs::read_dir("/sys/class/net")
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|entry| entry.file_name().to_string_lossy().to_string())
+                        .filter(|iface| iface != "lo")
+                        .collect()
+                })
+                .unwrap_or_default();
+            ifaces.sort();
+            ifaces
+        } else {
+            default_route_iface().into_iter().collect()
+        };
+
+        let mut addrs = if ctx.wants("ipv4") || ctx.wants("ipv6") {
+            iface_addrs()
+        } else {
+            HashMap::new()
+        };
+
+        Ok(ifaces
+            .into_iter()
+            .map(|iface| {
+                let read = |file: &str| {
+                    fs::read_to_string(format!("/sys/class/net/{iface}/{file}"))
+                        .map(|val| val.trim().to_string())
+                        .ok()
+                };
+
+                let mut fields = Fields::new();
+
+                let (ipv4, ipv6) = addrs.remove(&iface).unwrap_or_default();
+                if !ipv4.is_empty() {
+                    fields.insert("ipv4", Value::Text(ipv4.join(", ")));
+                }
+                if !ipv6.is_empty() {
+                    fields.insert("ipv6", Value::Text(ipv6.join(", ")));
+                }
+                if let Some(mac) = read("address") {
+                    fields.insert("mac", Value::Text(mac));
+                }
+                if let Some(state) = read("operstate") {
+                    fields.insert("state", Value::Text(state));
+                }
+                // Speed is in Mb/s, and -1 or unreadable when the link is down or virtual.
+                if let Some(speed) = read("speed").and_then(|speed| speed.parse::<i64>().ok())
+                    && speed > 0
+                {
+                    fields.insert("speed", Value::Int(speed));
+                }
+                if ctx.wants("ssid") {
+                    fields.insert("ssid", Value::Text(ssid(&iface).unwrap_or_default()));
+                }
+                fields.insert("iface", Value::Text(iface));
+
+                fields
+            })
+            .collect())
+    }
+}
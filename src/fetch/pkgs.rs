@@ -0,0 +1,85 @@
+use super::{Context, FetchError, Fetcher, Fields, Value, read_os_release, run};
+use std::fs;
+
+pub struct Pkgs;
+
+fn native_count(manager: &str) -> Result<i64, FetchError> {
+    let count = match manager {
+        "pacman" => run("pacman", &["-Q"])?.lines().count(),
+        // dpkg also lists removed packages that left config files behind.
+        "dpkg" => run("dpkg", &["-l"])?
+            .lines()
+            .filter(|line| line.starts_with("ii"))
+            .count(),
+        "xbps" => run("xbps-query", &["-l"])?.lines().count(),
+        "rpm" => run("rpm", &["-qa"])?.lines().count(),
+        "portage" => fs::read_dir("/var/db/pkg")
+            .map(|categories| {
+                categories
+                    .flatten()
+                    .filter_map(|cat| fs::read_dir(cat.path()).ok())
+                    .map(|pkgs| pkgs.flatten().count())
+                    .sum()
+            })
+            .unwrap_or(0),
+        other => {
+            return Err(FetchError::Unsupported(format!(
+                "Package manager {other} is not supported!"
+            )));
+        }
+    };
+
+    Ok(count as i64)
+}
+
+impl Fetcher for Pkgs {
+    fn name(&self) -> &'static str {
+        "pkgs"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["native", "flatpak", "snap", "manager"]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let distro_id = read_os_release("ID_LIKE=", Some("ID="))?;
+
+        let manager = distro_id
+            .split(' ')
+            .find_map(|distro| match distro {
+                "arch" | "artix" => Some("pacman"),
+                "debian" => Some("dpkg"),
+                "void" => Some("xbps"),
+                "rhel" => Some("rpm"),
+                "gentoo" => Some("portage"),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                FetchError::Unsupported(format!(
+                    "Failed to identify package manager: {distro_id}, distro is not supported!"
+                ))
+            })?;
+
+        let mut fields = Fields::new();
+        fields.insert("manager", Value::from(manager));
+        if ctx.wants("native")
+            && let Ok(native) = native_count(manager)
+        {
+            fields.insert("native", Value::Int(native));
+        }
+        if ctx.wants("flatpak")
+            && let Ok(flatpak) = run("flatpak", &["list", "--app"])
+        {
+            fields.insert("flatpak", Value::Int(flatpak.lines().count() as i64));
+        }
+        if ctx.wants("snap")
+            && let Ok(snap) = run("snap", &["list"])
+        {
+            // Skip the header line.
+            let count = snap.lines().count().saturating_sub(1);
+            fields.insert("snap", Value::Int(count as i64));
+        }
+
+        Ok(vec![fields])
+    }
+}
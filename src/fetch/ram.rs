@@ -0,0 +1,70 @@
+use super::{Context, FetchError, Fetcher, Fields, Unit, Value, percentage, read};
+use std::collections::HashMap;
+
+pub struct Ram;
+
+impl Fetcher for Ram {
+    fn name(&self) -> &'static str {
+        "ram"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &[
+            "total",
+            "used",
+            "free",
+            "used_percentage",
+            "free_percentage",
+            "swap_total",
+            "swap_used",
+            "swap_free",
+            "swap_used_percentage",
+            "swap_free_percentage",
+        ]
+    }
+
+    fn fetch(&self, _ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let memfile = read("/proc/meminfo")?;
+
+        // Values are in KiB.
+        let mut values = HashMap::new();
+        for line in memfile.lines() {
+            if let Some((key, val)) = line.split_once(':')
+                && let Some(num) = val
+                    .split_whitespace()
+                    .next()
+                    .and_then(|num| num.parse::<u64>().ok())
+            {
+                values.insert(key, num * 1024);
+            }
+        }
+
+        let get = |key: &str| {
+            values.get(key).copied().ok_or(FetchError::Parse {
+                path: String::from("/proc/meminfo"),
+                what: "memory info",
+            })
+        };
+
+        let total = get("MemTotal")?;
+        let free = get("MemAvailable")?;
+        let used = total - free;
+
+        let mut fields = Fields::new();
+        fields.insert("total", Value::Size(total, Unit::MiB));
+        fields.insert("used", Value::Size(used, Unit::MiB));
+        fields.insert("free", Value::Size(free, Unit::MiB));
+        fields.insert("used_percentage", percentage(used, total));
+        fields.insert("free_percentage", percentage(free, total));
+        if let (Ok(swap_total), Ok(swap_free)) = (get("SwapTotal"), get("SwapFree")) {
+            let swap_used = swap_total - swap_free;
+            fields.insert("swap_total", Value::Size(swap_total, Unit::MiB));
+            fields.insert("swap_used", Value::Size(swap_used, Unit::MiB));
+            fields.insert("swap_free", Value::Size(swap_free, Unit::MiB));
+            fields.insert("swap_used_percentage", percentage(swap_used, swap_total));
+            fields.insert("swap_free_percentage", percentage(swap_free, swap_total));
+        }
+
+        Ok(vec![fields])
+    }
+}
@@ -0,0 +1,135 @@
+use super::{Context, FetchError, Fetcher, Fields, Value, process_info};
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use std::{env, fs, process::Command};
+
+pub struct Shell;
+
+pub(super) const SHELLS: &[&str] = &[
+    "bash", "zsh", "fish", "sh", "dash", "ksh", "mksh", "oksh", "tcsh", "csh", "nu", "elvish",
+    "xonsh", "yash", "pwsh",
+];
+
+// Shells that have no version flag.
+const NO_VERSION: &[&str] = &["sh", "dash", "csh"];
+
+fn basename(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// The nearest shell among our ancestors, as the path of its executable.
+fn parent_shell() -> Option<String> {
+    let (mut pid, _) = process_info(std::process::id())?;
+
+    while pid > 1 {
+        let (ppid, name) = process_info(pid)?;
+        if SHELLS.contains(&name.as_str()) {
+            return fs::read_link(format!("/proc/{pid}/exe"))
+                .ok()
+                .map(|exe| exe.to_string_lossy().to_string());
+        }
+        pid = ppid;
+    }
+
+    None
+}
+
+fn cache_file() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::home_dir().map(|home| home.join(".cache")))
+        .map(|dir| dir.join("corrfetch/shell_versions"))
+}
+
+/// The version of the shell at `path`. Asking a shell for its version can
+/// take a while (fish especially), so versions are cached by path and
+/// modification time in `$XDG_CACHE_HOME/corrfetch/shell_versions`.
+fn version(path: &str) -> Option<String> {
+    let name = basename(path);
+    if NO_VERSION.contains(&name) {
+        return None;
+    }
+
+    // Only set if the user exported it, but free when they did.
+    let var = format!("{}_VERSION", name.to_uppercase());
+    if let Ok(version) = env::var(var) {
+        return Some(version);
+    }
+
+    let modified = fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs()
+        .to_string();
+
+    let cache_file = cache_file();
+    let mut cache = cache_file
+        .as_ref()
+        .and_then(|file| fs::read_to_string(file).ok())
+        .unwrap_or_default();
+    let cached = cache.lines().find_map(|line| {
+        let mut cols = line.split('\t');
+        (cols.next()? == path && cols.next()? == modified).then(|| cols.next().map(String::from))?
+    });
+    if cached.is_some() {
+        return cached;
+    }
+
+    let out = Command::new(path).arg("--version").output().ok()?;
+    let re = regex::Regex::new(r"\d+(\.\d+)+").unwrap();
+    let version = re
+        .find(&String::from_utf8_lossy(&out.stdout))?
+        .as_str()
+        .to_string();
+
+    // Older entries for the same shell are dropped.
+    cache = cache
+        .lines()
+        .filter(|line| line.split('\t').next() != Some(path))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    cache.push_str(&format!("{path}\t{modified}\t{version}\n"));
+    if let Some(file) = cache_file {
+        // The cache is only an optimisation, failing to write it is fine.
+        let _ = file.parent().map(fs::create_dir_all);
+        let _ = fs::write(file, cache);
+    }
+
+    Some(version)
+}
+
+impl Fetcher for Shell {
+    fn name(&self) -> &'static str {
+        "shell"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["shell", "version", "path", "login_shell"]
+    }
+
+    /// The shell corrfetch runs in, or the login shell from `$SHELL` if there
+    /// is no shell among its parent processes.
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let login_shell = env::var("SHELL").ok();
+        let path = parent_shell()
+            .or_else(|| login_shell.clone())
+            .ok_or(FetchError::Env("SHELL"))?;
+
+        let mut fields = Fields::new();
+        fields.insert("shell", Value::from(basename(&path)));
+        if ctx.wants("version")
+            && let Some(version) = version(&path)
+        {
+            fields.insert("version", Value::Text(version));
+        }
+        if let Some(login_shell) = login_shell {
+            fields.insert("login_shell", Value::from(basename(&login_shell)));
+        }
+        fields.insert("path", Value::Text(path));
+
+        Ok(vec![fields])
+    }
+}
@@ -0,0 +1,140 @@
+use super::{Context, FetchError, Fetcher, Fields, Unit, Value, percentage, read};
+use std::fs;
+
+pub struct Swap;
+
+struct SwapDevice {
+    name: String,
+    kind: String,
+    size: u64,
+    used: u64,
+    priority: i32,
+    zram_ratio: Option<f64>,
+}
+
+fn zram_ratio(device: &str) -> Option<f64> {
+    let name = device.strip_prefix("/dev/")?;
+    let mm_stat = fs::read_to_string(format!("/sys/block/{name}/mm_stat")).ok()?;
+
+    // First two columns are orig_data_size and compr_data_size, in bytes.
+    let mut stats = mm_stat
+        .split_whitespace()
+        .map(|stat| stat.parse::<u64>().unwrap_or(0));
+    let orig = stats.next()?;
+    let compr = stats.next()?;
+
+    (compr > 0).then(|| orig as f64 / compr as f64)
+}
+
+fn swap_devices() -> Vec<SwapDevice> {
+    let swaps = fs::read_to_string("/proc/swaps").unwrap_or_default();
+
+    swaps
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            if cols.len() < 5 {
+                return None;
+            }
+
+            // Filenames with spaces are escaped as \040 by the kernel.
+            let name = cols[0].replace("\\040", " ");
+            let zram_ratio = zram_ratio(&name);
+            let kind = if name.starts_with("/dev/zram") {
+                String::from("zram")
+            } else {
+                cols[1].to_string()
+            };
+
+            Some(SwapDevice {
+                name,
+                kind,
+                size: cols[2].parse::<u64>().unwrap_or(0) / 1024,
+                used: cols[3].parse::<u64>().unwrap_or(0) / 1024,
+                priority: cols[4].parse().unwrap_or(0),
+                zram_ratio,
+            })
+        })
+        .collect()
+}
+
+impl Fetcher for Swap {
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &[
+            "total",
+            "used",
+            "free",
+            "free_percentage",
+            "used_percentage",
+            "devices",
+            "zram_ratio",
+        ]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let meminfo = read("/proc/meminfo")?;
+        let read_bytes = |key: &str| {
+            meminfo
+                .lines()
+                .find_map(|line| {
+                    line.strip_prefix(key)?
+                        .split_whitespace()
+                        .next()?
+                        .parse::<u64>()
+                        .ok()
+                })
+                .map(|kib| kib * 1024)
+                .ok_or(FetchError::Parse {
+                    path: String::from("/proc/meminfo"),
+                    what: "swap info",
+                })
+        };
+
+        let total = read_bytes("SwapTotal:")?;
+        let free = read_bytes("SwapFree:")?;
+        let used = total - free;
+
+        let swap_devices = if ctx.wants("devices") || ctx.wants("zram_ratio") {
+            swap_devices()
+        } else {
+            Vec::new()
+        };
+
+        let devices = swap_devices
+            .iter()
+            .map(|dev| {
+                let ratio = dev
+                    .zram_ratio
+                    .map(|ratio| format!(", {ratio:.1}x"))
+                    .unwrap_or_default();
+                format!(
+                    "{} ({}, prio {}, {}/{} MiB{ratio})",
+                    dev.name, dev.kind, dev.priority, dev.used, dev.size
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut fields = Fields::new();
+        fields.insert("total", Value::Size(total, Unit::MiB));
+        fields.insert("used", Value::Size(used, Unit::MiB));
+        fields.insert("free", Value::Size(free, Unit::MiB));
+        fields.insert("free_percentage", percentage(free, total));
+        fields.insert("used_percentage", percentage(used, total));
+        fields.insert("devices", Value::Text(devices));
+        if let Some(ratio) = swap_devices
+            .iter()
+            .filter_map(|dev| dev.zram_ratio)
+            .reduce(f64::max)
+        {
+            fields.insert("zram_ratio", Value::Float(ratio));
+        }
+
+        Ok(vec![fields])
+    }
+}
@@ -0,0 +1,231 @@
+use super::shell::SHELLS;
+use super::{Context, FetchError, Fetcher, Fields, Value, process_info, run};
+use crate::cfg_parser::config_home;
+use std::fs;
+
+pub struct Terminal;
+
+// Besides shells, processes that can sit between corrfetch and the terminal
+// emulator.
+const SKIPPED: &[&str] = &[
+    "tmux: client",
+    "tmux",
+    "screen",
+    "SCREEN",
+    "zellij",
+    "sudo",
+    "doas",
+    "su",
+    "login",
+    "script",
+];
+
+// Process names (cut to 15 characters like in /proc) and how to show them.
+const NAMES: &[(&str, &str)] = &[
+    ("alacritty", "Alacritty"),
+    ("kitty", "kitty"),
+    ("foot", "foot"),
+    ("footclient", "foot"),
+    ("wezterm-gui", "WezTerm"),
+    ("ghostty", "Ghostty"),
+    ("gnome-terminal-", "GNOME Terminal"),
+    ("kgx", "GNOME Console"),
+    ("konsole", "Konsole"),
+    ("xfce4-terminal", "Xfce Terminal"),
+    ("tilix", "Tilix"),
+    ("urxvt", "urxvt"),
+    ("xterm", "xterm"),
+    ("st", "st"),
+];
+
+fn multiplexer() -> Option<&'static str> {
+    let is_set = |var: &str| std::env::var_os(var).is_some_and(|val| !val.is_empty());
+
+    if is_set("TMUX") {
+        Some("tmux")
+    } else if is_set("STY") {
+        Some("screen")
+    } else if is_set("ZELLIJ") {
+        Some("zellij")
+    } else {
+        None
+    }
+}
+
+/// Walks up from our parent process looking for a known terminal, as
+/// (known, first unknown). Wrappers like `timeout`, `watch` or an editor can
+/// sit in between, so the first process that is not a shell, multiplexer or
+/// privilege tool is only kept as a guess. Under tmux the chain ends at the
+/// tmux server, so the walk continues from the attached client.
+fn find_terminal() -> (Option<String>, Option<String>) {
+    let mut unknown = None;
+    let mut hopped = false;
+    let Some((mut pid, _)) = process_info(std::process::id()) else {
+        return (None, None);
+    };
+
+    while pid > 1 {
+        let Some((ppid, name)) = process_info(pid) else {
+            break;
+        };
+
+        if name == "tmux: server" {
+            // In nested tmux, $TMUX only leads back to the inner client, so
+            // a second server ends the walk instead of looping.
+            if hopped {
+                break;
+            }
+            hopped = true;
+            match run("tmux", &["display-message", "-p", "#{client_pid}"])
+                .ok()
+                .and_then(|client| client.trim().parse().ok())
+            {
+                Some(client) => pid = client,
+                None => break,
+            }
+            continue;
+        }
+        // Logged in remotely, the terminal is on the other end.
+        if name.starts_with("sshd") {
+            return (None, None);
+        }
+        if NAMES.iter().any(|(known, _)| *known == name) {
+            return (Some(name), unknown);
+        }
+        if unknown.is_none()
+            && !SHELLS.contains(&name.as_str())
+            && !SKIPPED.contains(&name.as_str())
+        {
+            unknown = Some(name);
+        }
+
+        pid = ppid;
+    }
+
+    (None, unknown)
+}
+
+fn alacritty_font() -> (Option<String>, Option<String>) {
+    let config = config_home()
+        .and_then(|dir| fs::read_to_string(dir.join("alacritty/alacritty.toml")).ok())
+        .and_then(|config| config.parse::<toml::Table>().ok());
+    let font = config.as_ref().and_then(|config| config.get("font"));
+
+    let family = font
+        .and_then(|font| font.get("normal"))
+        .and_then(|normal| normal.get("family"))
+        .and_then(|family| family.as_str())
+        .map(String::from);
+    let size = font
+        .and_then(|font| font.get("size"))
+        .map(|size| match size {
+            toml::Value::String(size) => size.clone(),
+            size => size.to_string(),
+        });
+
+    (family, size)
+}
+
+fn kitty_font() -> (Option<String>, Option<String>) {
+    let config = config_home()
+        .and_then(|dir| fs::read_to_string(dir.join("kitty/kitty.conf")).ok())
+        .unwrap_or_default();
+    let option = |name: &str| {
+        config.lines().find_map(|line| {
+            let (key, value) = line.trim().split_once(char::is_whitespace)?;
+            (key == name).then(|| value.trim().to_string())
+        })
+    };
+
+    (option("font_family"), option("font_size"))
+}
+
+/// From `font=<family>:size=<size>`, ignoring fallback fonts after a comma.
+fn foot_font() -> (Option<String>, Option<String>) {
+    let font = config_home()
+        .and_then(|dir| fs::read_to_string(dir.join("foot/foot.ini")).ok())
+        .and_then(|config| {
+            config.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "font").then(|| value.trim().to_string())
+            })
+        });
+    let Some(font) = font else {
+        return (None, None);
+    };
+
+    let font = font.split(',').next().unwrap_or_default();
+    let mut parts = font.split(':');
+    let family = parts.next().map(|family| family.trim().to_string());
+    let size = parts.find_map(|part| part.trim().strip_prefix("size=").map(String::from));
+
+    (family, size)
+}
+
+fn wezterm_font() -> (Option<String>, Option<String>) {
+    let config = config_home()
+        .and_then(|dir| fs::read_to_string(dir.join("wezterm/wezterm.lua")).ok())
+        .or_else(|| {
+            std::env::home_dir().and_then(|home| fs::read_to_string(home.join(".wezterm.lua")).ok())
+        })
+        .unwrap_or_default();
+
+    let family = regex::Regex::new(r#"wezterm\.font(?:_with_fallback)?\s*\(\s*\{?\s*["']([^"']+)"#)
+        .unwrap()
+        .captures(&config)
+        .map(|cap| cap[1].to_string());
+    let size = regex::Regex::new(r"font_size\s*=\s*([\d.]+)")
+        .unwrap()
+        .captures(&config)
+        .map(|cap| cap[1].to_string());
+
+    (family, size)
+}
+
+impl Fetcher for Terminal {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["terminal", "font", "font_size", "multiplexer"]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let mut fields = Fields::new();
+
+        let (process, unknown) = find_terminal();
+        let terminal = process
+            .as_deref()
+            .and_then(|process| NAMES.iter().find(|(name, _)| *name == process))
+            .map(|(_, shown)| shown.to_string())
+            .or_else(|| std::env::var("TERM_PROGRAM").ok())
+            .or(unknown)
+            .or_else(|| std::env::var("TERM").ok());
+        if let Some(terminal) = terminal {
+            fields.insert("terminal", Value::Text(terminal));
+        }
+
+        if ctx.wants("font") || ctx.wants("font_size") {
+            let (font, size) = match process.as_deref() {
+                Some("alacritty") => alacritty_font(),
+                Some("kitty") => kitty_font(),
+                Some("foot" | "footclient") => foot_font(),
+                Some("wezterm-gui") => wezterm_font(),
+                _ => (None, None),
+            };
+            if let Some(font) = font {
+                fields.insert("font", Value::Text(font));
+            }
+            if let Some(size) = size {
+                fields.insert("font_size", Value::Text(size));
+            }
+        }
+
+        if let Some(multiplexer) = multiplexer() {
+            fields.insert("multiplexer", Value::from(multiplexer));
+        }
+
+        Ok(vec![fields])
+    }
+}
@@ -0,0 +1,36 @@
+use super::{Context, FetchError, Fetcher, Fields, Value, carry_time_units, read};
+
+pub struct Uptime;
+
+impl Fetcher for Uptime {
+    fn name(&self) -> &'static str {
+        "uptime"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["d", "h", "m", "s"]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let contents = read("/proc/uptime")?;
+
+        let uptime: i64 = contents
+            .split('.')
+            .next()
+            .and_then(|secs| secs.parse().ok())
+            .ok_or(FetchError::Parse {
+                path: String::from("/proc/uptime"),
+                what: "uptime",
+            })?;
+
+        let mut fields = Fields::new();
+        fields.insert("d", Value::Int(uptime / 86400));
+        fields.insert("h", Value::Int((uptime % 86400) / 3600));
+        fields.insert("m", Value::Int((uptime % 3600) / 60));
+        fields.insert("s", Value::Int(uptime % 60));
+
+        carry_time_units(ctx, &mut fields);
+
+        Ok(vec![fields])
+    }
+}
@@ -0,0 +1,30 @@
+use super::{Context, FetchError, Fetcher, Fields, Value};
+use std::env;
+
+pub struct Username;
+
+impl Fetcher for Username {
+    fn name(&self) -> &'static str {
+        "username"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["user", "host"]
+    }
+
+    fn fetch(&self, _ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let mut fields = Fields::new();
+        if let Ok(user) = env::var("USER") {
+            fields.insert("user", Value::Text(user));
+        }
+        if let Ok(host) = hostname::get() {
+            fields.insert("host", Value::Text(host.to_string_lossy().to_string()));
+        }
+
+        if fields.is_empty() {
+            return Err(FetchError::Env("USER"));
+        }
+
+        Ok(vec![fields])
+    }
+}
@@ -0,0 +1,125 @@
+use super::{Context, FetchError, Fetcher, Fields, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::process::Command;
+
+pub struct Wm;
+
+// Process name (as in /proc/<pid>/comm, which is cut to 15 characters), shown
+// name, display protocol if it only speaks one, and the flag printing its
+// version. Compositors come first, as X window managers can run nested in them.
+const KNOWN: &[(&str, &str, Option<&str>, &str)] = &[
+    ("sway", "Sway", Some("wayland"), "--version"),
+    ("Hyprland", "Hyprland", Some("wayland"), "--version"),
+    ("river", "river", Some("wayland"), "-version"),
+    ("niri", "niri", Some("wayland"), "--version"),
+    ("wayfire", "Wayfire", Some("wayland"), "--version"),
+    ("labwc", "labwc", Some("wayland"), "--version"),
+    ("hikari", "hikari", Some("wayland"), "-v"),
+    ("dwl", "dwl", Some("wayland"), "-v"),
+    ("cage", "Cage", Some("wayland"), "-v"),
+    ("weston", "Weston", Some("wayland"), "--version"),
+    ("kwin_wayland", "KWin", Some("wayland"), "--version"),
+    ("kwin_x11", "KWin", Some("x11"), "--version"),
+    ("gnome-shell", "Mutter", None, "--version"),
+    ("i3", "i3", Some("x11"), "--version"),
+    ("bspwm", "bspwm", Some("x11"), "-v"),
+    ("dwm", "dwm", Some("x11"), "-v"),
+    ("awesome", "awesome", Some("x11"), "--version"),
+    ("openbox", "Openbox", Some("x11"), "--version"),
+    ("fluxbox", "Fluxbox", Some("x11"), "-version"),
+    ("herbstluftwm", "herbstluftwm", Some("x11"), "--version"),
+    ("xmonad", "xmonad", Some("x11"), "--version"),
+    ("qtile", "Qtile", None, "--version"),
+    ("spectrwm", "spectrwm", Some("x11"), "-v"),
+    ("leftwm", "LeftWM", Some("x11"), "--version"),
+    ("icewm", "IceWM", Some("x11"), "--version"),
+    ("jwm", "JWM", Some("x11"), "-v"),
+    ("xfwm4", "Xfwm4", Some("x11"), "--version"),
+    ("marco", "Marco", Some("x11"), "--version"),
+    ("muffin", "Muffin", Some("x11"), "--version"),
+    ("compiz", "Compiz", Some("x11"), "--version"),
+    ("enlightenment", "Enlightenment", None, "-version"),
+];
+
+/// The name and pid of every running process.
+fn processes() -> HashMap<String, String> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_string_lossy().to_string();
+            if !pid.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let comm = fs::read_to_string(entry.path().join("comm")).ok()?;
+            Some((comm.trim().to_string(), pid))
+        })
+        .collect()
+}
+
+/// Asks the running binary for its version, which some print to stderr.
+fn version(pid: &str, name: &str, flag: &str) -> Option<String> {
+    let program = fs::read_link(format!("/proc/{pid}/exe"))
+        .map(|exe| exe.to_string_lossy().to_string())
+        .unwrap_or_else(|_| name.to_string());
+    let out = Command::new(program).arg(flag).output().ok()?;
+
+    let re = regex::Regex::new(r"\d+(\.\d+)+").unwrap();
+    [out.stdout, out.stderr].iter().find_map(|output| {
+        re.find(&String::from_utf8_lossy(output))
+            .map(|version| version.as_str().to_string())
+    })
+}
+
+impl Fetcher for Wm {
+    fn name(&self) -> &'static str {
+        "wm"
+    }
+
+    fn placeholders(&self) -> &'static [&'static str] {
+        &["wm", "protocol", "version"]
+    }
+
+    fn fetch(&self, ctx: &Context) -> Result<Vec<Fields>, FetchError> {
+        let processes = processes();
+
+        let (comm, name, protocol, flag) = KNOWN
+            .iter()
+            .find_map(|&(comm, name, protocol, flag)| {
+                // xmonad runs as a binary named after the platform, like
+                // xmonad-x86_64-linux.
+                let running = processes.keys().find(|running| {
+                    *running == comm || (comm == "xmonad" && running.starts_with("xmonad-"))
+                })?;
+                Some((running.clone(), name, protocol, flag))
+            })
+            .ok_or_else(|| FetchError::Unsupported(String::from("No window manager found!")))?;
+
+        let is_set = |var: &str| std::env::var_os(var).is_some_and(|val| !val.is_empty());
+        let protocol = if is_set("WAYLAND_DISPLAY") {
+            Some("wayland")
+        } else if is_set("DISPLAY") {
+            Some("x11")
+        } else {
+            // Not running inside the session, e.g. over SSH.
+            protocol
+        };
+
+        let mut fields = Fields::new();
+        fields.insert("wm", Value::from(name));
+        if let Some(protocol) = protocol {
+            fields.insert("protocol", Value::from(protocol));
+        }
+        if ctx.wants("version")
+            && let Some(version) = version(&processes[&comm], &comm, flag)
+        {
+            fields.insert("version", Value::Text(version));
+        }
+
+        Ok(vec![fields])
+    }
+}
@@ -1,181 +1,414 @@
-use crate::cfg_parser::Config;
-use regex::Regex;
-use std::collections::HashMap;
+use crate::cfg_parser::{Bar, Config, Format};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use crate::fetch;
-use strfmt::strfmt;
+use crate::color::Palette;
+use crate::fetch::{self, Cache, Fetcher, Fields, Value};
+use crate::layout::display_width;
+use strfmt::{FmtError, strfmt};
+use unicode_width::UnicodeWidthChar;
 
-fn process_time_strings(
-    format_str: &str,
-    mut y: i32,
-    mut mo: i32,
-    mut d: i32,
-    mut h: i32,
-    mut m: i32,
-    mut s: i32,
-) -> Result<String, Box<dyn std::error::Error>> {
-    let re = Regex::new(r"\{(\w+)\}")?;
-
-    let used_vars: std::collections::HashSet<String> = re
-        .captures_iter(format_str)
-        .map(|cap| cap[1].to_string())
-        .collect();
+// Placeholders a fetcher declares but could not fill in.
+pub const UNKNOWN: &str = "unknown";
+// Default for every placeholder of a key that did not finish in time.
+const TIMED_OUT: &str = "...";
+// A placeholder with filters, like `{total|gib:1}`.
+const FILTERED: &str = r"\{(\w+)((?:\|[^{}|]+)+)\}";
+// A gauge of a percentage placeholder, like `{bar:used_percentage}`.
+const BAR: &str = r"\{bar:(\w+)\}";
+
+/// The `if` of a format rule, e.g. `used_percentage > 80`.
+pub struct Condition<'a> {
+    pub placeholder: &'a str,
+    op: &'a str,
+    value: &'a str,
+}
 
-    if !used_vars.contains("y") && used_vars.contains("mo") {
-        mo += y * 12;
-        y = 0;
+impl<'a> Condition<'a> {
+    pub fn parse(condition: &'a str) -> Option<Self> {
+        let re = regex::Regex::new(r"^\s*(\w+)\s*(>=|<=|==|!=|>|<)\s*(.*?)\s*$").unwrap();
+        let caps = re.captures(condition)?;
+
+        Some(Condition {
+            placeholder: caps.get(1)?.as_str(),
+            op: caps.get(2)?.as_str(),
+            value: caps.get(3)?.as_str().trim_matches('"'),
+        })
     }
 
-    if !used_vars.contains("mo") && used_vars.contains("d") {
-        d += mo * 30;
-        mo = 0;
+    /// Compares numerically when both sides are numbers (as displayed, so
+    /// sizes are in the unit they are shown in), otherwise only `==` and
+    /// `!=` can match.
+    fn matches(&self, fields: &Fields) -> bool {
+        let Some(value) = fields.get(self.placeholder) else {
+            return false;
+        };
+        let value = value.to_string();
+
+        match (value.parse::<f64>(), self.value.parse::<f64>()) {
+            (Ok(a), Ok(b)) => match self.op {
+                ">" => a > b,
+                ">=" => a >= b,
+                "<" => a < b,
+                "<=" => a <= b,
+                "==" => a == b,
+                _ => a != b,
+            },
+            _ => match self.op {
+                "==" => value == self.value,
+                "!=" => value != self.value,
+                _ => false,
+            },
+        }
     }
+}
+
+/// Every placeholder a key's format needs fetched: those in its format
+/// strings and those its rules compare.
+pub fn used_placeholders(format: &Format) -> HashSet<String> {
+    let mut used = fetch::placeholders_in(format.format_str());
 
-    if !used_vars.contains("d") && used_vars.contains("h") {
-        h += d * 24;
-        d = 0;
+    for rule in format.rules() {
+        if let Some(format_str) = &rule.format {
+            used.extend(fetch::placeholders_in(format_str));
+        }
+        if let Some(condition) = Condition::parse(&rule.condition) {
+            used.insert(condition.placeholder.to_string());
+        }
     }
 
-    if !used_vars.contains("h") && used_vars.contains("m") {
-        m += h * 60;
-        h = 0;
+    used
+}
+
+/// A `|filter` applied to a placeholder, e.g. `{total|gib:1}`.
+#[derive(Debug, Clone, Copy)]
+pub enum Filter {
+    Upper,
+    Lower,
+    /// At most this many columns wide.
+    Trunc(usize),
+    /// Padded with spaces at the end to this width.
+    Pad(usize),
+    /// Padded with spaces at the start to this width.
+    LPad(usize),
+    /// A size divided by the unit, shown with this many decimals.
+    Unit(u64, usize),
+    /// A size in bytes.
+    Bytes,
+}
+
+impl Filter {
+    pub fn parse(filter: &str) -> Result<Self, String> {
+        let (name, arg) = match filter.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg.trim())),
+            None => (filter.trim(), None),
+        };
+        let number = |default: Option<usize>| match arg {
+            Some(arg) => arg
+                .parse()
+                .map_err(|_| format!("invalid argument `{arg}` for filter `{name}`")),
+            None => {
+                default.ok_or_else(|| format!("filter `{name}` needs a number, like `{name}:10`"))
+            }
+        };
+
+        Ok(match name {
+            "upper" => Filter::Upper,
+            "lower" => Filter::Lower,
+            "trunc" => Filter::Trunc(number(None)?),
+            "pad" => Filter::Pad(number(None)?),
+            "lpad" => Filter::LPad(number(None)?),
+            "kib" => Filter::Unit(1 << 10, number(Some(0))?),
+            "mib" => Filter::Unit(1 << 20, number(Some(0))?),
+            "gib" => Filter::Unit(1 << 30, number(Some(0))?),
+            "tib" => Filter::Unit(1 << 40, number(Some(0))?),
+            "bytes" => Filter::Bytes,
+            _ => {
+                return Err(format!(
+                    "unknown filter `{name}` (available: upper, lower, trunc, pad, lpad, kib, mib, gib, tib, bytes)"
+                ));
+            }
+        })
     }
 
-    if !used_vars.contains("m") && used_vars.contains("s") {
-        s += m * 60;
-        m = 0;
+    /// Unit filters work on the exact byte count of sizes and leave other
+    /// values alone.
+    fn apply(self, value: Value) -> Value {
+        match (self, value) {
+            (Filter::Unit(unit, decimals), Value::Size(bytes, _)) => {
+                Value::Text(format!("{:.decimals$}", bytes as f64 / unit as f64))
+            }
+            (Filter::Bytes, Value::Size(bytes, _)) => Value::Int(bytes as i64),
+            (Filter::Unit(..) | Filter::Bytes, value) => value,
+            (Filter::Upper, value) => Value::Text(value.to_string().to_uppercase()),
+            (Filter::Lower, value) => Value::Text(value.to_string().to_lowercase()),
+            (Filter::Trunc(width), value) => {
+                let mut used = 0;
+                let text = value
+                    .to_string()
+                    .chars()
+                    .take_while(|c| {
+                        used += c.width().unwrap_or(0);
+                        used <= width
+                    })
+                    .collect();
+                Value::Text(text)
+            }
+            (Filter::Pad(width), value) => {
+                let text = value.to_string();
+                let padding = " ".repeat(width.saturating_sub(display_width(&text)));
+                Value::Text(format!("{text}{padding}"))
+            }
+            (Filter::LPad(width), value) => {
+                let text = value.to_string();
+                let padding = " ".repeat(width.saturating_sub(display_width(&text)));
+                Value::Text(format!("{padding}{text}"))
+            }
+        }
     }
+}
 
-    let mut vars = HashMap::new();
-    vars.insert("y".to_string(), y.to_string());
-    vars.insert("mo".to_string(), mo.to_string());
-    vars.insert("d".to_string(), d.to_string());
-    vars.insert("h".to_string(), h.to_string());
-    vars.insert("m".to_string(), m.to_string());
-    vars.insert("s".to_string(), s.to_string());
+/// Every `|filter` in a format string.
+pub fn filters_in(format_str: &str) -> Vec<String> {
+    let re = regex::Regex::new(FILTERED).unwrap();
 
-    Ok(strfmt(format_str, &vars)?)
+    re.captures_iter(format_str)
+        .flat_map(|cap| {
+            cap[2]
+                .split('|')
+                .skip(1)
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .collect()
 }
 
-fn format(config: &Config, key: &str) -> String {
-    let parsed_cfg = config
+/// Draws `value` (a percentage) as a bar, with the filled part colored by
+/// the configured color with the highest `from` it reached. Values that
+/// are not numbers, like timed out ones, are shown as they are.
+fn bar(config: Option<&Bar>, palette: &Palette, value: &Value) -> String {
+    let percentage = match value {
+        Value::Int(i) => *i as f64,
+        Value::Float(x) => *x,
+        value => return value.to_string(),
+    };
+
+    let width = config.and_then(|bar| bar.width).unwrap_or(10);
+    let filled_char = config.and_then(|bar| bar.filled).unwrap_or('█');
+    let empty_char = config.and_then(|bar| bar.empty).unwrap_or('░');
+
+    let filled = ((percentage.clamp(0.0, 100.0) / 100.0 * width as f64).round()) as usize;
+    let filled_part = filled_char.to_string().repeat(filled);
+    let empty_part = empty_char.to_string().repeat(width - filled);
+
+    let color = config
+        .and_then(|bar| bar.colors.as_ref())
+        .and_then(|colors| {
+            colors
+                .iter()
+                .filter(|color| percentage >= color.from)
+                .max_by(|a, b| a.from.total_cmp(&b.from))
+        });
+
+    match color {
+        Some(color) => format!("{}{empty_part}", palette.paint(&color.color, &filled_part)),
+        None => format!("{filled_part}{empty_part}"),
+    }
+}
+
+fn try_render(
+    config: &Config,
+    palette: &Palette,
+    fetcher: &dyn Fetcher,
+    format_str: &str,
+    fields: &Fields,
+) -> Result<String, FmtError> {
+    let value_of = |name: &str| {
+        fields
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| Value::from(UNKNOWN))
+    };
+
+    // Color markup, bars and filtered placeholders are filled in here, strfmt
+    // does the rest.
+    let format_str = palette.markup(format_str);
+    let re = regex::Regex::new(BAR).unwrap();
+    let format_str = re.replace_all(&format_str, |cap: &regex::Captures| {
+        bar(config.bar.as_ref(), palette, &value_of(&cap[1]))
+            .replace('{', "{{")
+            .replace('}', "}}")
+    });
+    let re = regex::Regex::new(FILTERED).unwrap();
+    let format_str = re.replace_all(&format_str, |cap: &regex::Captures| {
+        let value = cap[2]
+            .split('|')
+            .skip(1)
+            .filter_map(|filter| Filter::parse(filter).ok())
+            .fold(value_of(&cap[1]), |value, filter| filter.apply(value));
+
+        value.to_string().replace('{', "{{").replace('}', "}}")
+    });
+
+    let mut vars: HashMap<String, String> = fetcher
+        .placeholders()
+        .iter()
+        .map(|name| (name.to_string(), UNKNOWN.to_string()))
+        .collect();
+    vars.extend(
+        fields
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string())),
+    );
+
+    strfmt(&format_str, &vars)
+}
+
+fn render(
+    config: &Config,
+    palette: &Palette,
+    fetcher: &dyn Fetcher,
+    format_str: &str,
+    fields: &Fields,
+) -> String {
+    try_render(config, palette, fetcher, format_str, fields).unwrap()
+}
+
+/// Renders `format_str` with nothing fetched, to catch format specs strfmt
+/// rejects (like `{used:q}`) before they fail at run time.
+pub fn validate(config: &Config, fetcher: &dyn Fetcher, format_str: &str) -> Result<(), String> {
+    let palette = Palette::new(config);
+
+    match try_render(config, &palette, fetcher, format_str, &Fields::new()) {
+        Ok(_) => Ok(()),
+        Err(FmtError::Invalid(e) | FmtError::KeyError(e) | FmtError::TypeError(e)) => Err(e),
+    }
+}
+
+fn format(
+    config: &Config,
+    palette: &Palette,
+    cache: &mut Cache,
+    fetcher: &'static dyn Fetcher,
+) -> Vec<String> {
+    let key = fetcher.name();
+    let format = config
         .format
         .get(key)
-        .unwrap_or_else(|| panic!("'{}' key not found in config 'format' section!", key))
-        .as_str();
+        .unwrap_or_else(|| panic!("'{}' key not found in config 'format' section!", key));
+    let parsed_cfg = format.format_str();
 
-    let mut vars = HashMap::new();
+    match cache.get(fetcher) {
+        Some(Ok(lines)) => lines
+            .iter()
+            .map(|fields| {
+                // The first rule that matches wins.
+                let rule = format.rules().iter().find(|rule| {
+                    Condition::parse(&rule.condition).is_some_and(|c| c.matches(fields))
+                });
+                let format_str = rule
+                    .and_then(|rule| rule.format.as_deref())
+                    .unwrap_or(parsed_cfg);
 
-    match key {
-        "batt" => {
-            vars.insert("level".to_string(), fetch::batt().level);
-            vars.insert("status".to_string(), fetch::batt().status);
-        }
-        "ram" => {
-            vars.insert("total".to_string(), fetch::ram().total);
-            vars.insert("used".to_string(), fetch::ram().used);
-            vars.insert("free".to_string(), fetch::ram().free);
-            vars.insert("used_percentage".to_string(), fetch::ram().used_percentage);
-            vars.insert("free_percentage".to_string(), fetch::ram().free_percentage);
-            vars.insert("swap_total".to_string(), fetch::ram().swap_total);
-            vars.insert("swap_used".to_string(), fetch::ram().swap_used);
-            vars.insert("swap_free".to_string(), fetch::ram().swap_free);
-            vars.insert(
-                "swap_used_percentage".to_string(),
-                fetch::ram().swap_used_percentage,
-            );
-            vars.insert(
-                "swap_free_percentage".to_string(),
-                fetch::ram().swap_free_percentage,
-            );
-        }
-        "swap" => {
-            vars.insert("total".to_string(), fetch::swap().total);
-            vars.insert("used".to_string(), fetch::swap().used);
-            vars.insert("free".to_string(), fetch::swap().free);
-            vars.insert("free_percentage".to_string(), fetch::swap().free_percentage);
-            vars.insert("used_percentage".to_string(), fetch::swap().used_percentage);
-        }
-        "uptime" => {
-            return process_time_strings(
-                parsed_cfg,
-                0,
-                0,
-                fetch::uptime().d,
-                fetch::uptime().h,
-                fetch::uptime().m,
-                fetch::uptime().s,
-            )
-            .unwrap();
-        }
-        "lifetime" => {
-            return process_time_strings(
-                parsed_cfg,
-                fetch::lifetime().y,
-                fetch::lifetime().mo,
-                fetch::lifetime().d,
-                fetch::lifetime().h,
-                fetch::lifetime().m,
-                fetch::lifetime().s,
-            )
-            .unwrap();
-        }
-        "de" => {
-            vars.insert("de".to_string(), fetch::de().de);
-        }
-        "kernel" => {
-            vars.insert("kernel".to_string(), fetch::kernel().kernel);
+                // As markup, so the line keeps its color after nested tags.
+                match rule.and_then(|rule| rule.color.as_deref()) {
+                    Some(color) => {
+                        let format_str = format!("<{color}>{format_str}</>");
+                        render(config, palette, fetcher, &format_str, fields)
+                    }
+                    None => render(config, palette, fetcher, format_str, fields),
+                }
+            })
+            .collect(),
+        Some(Err(e)) => vec![e.to_string()],
+        None => {
+            let placeholder = config.timeout_placeholder.as_deref().unwrap_or(TIMED_OUT);
+            let fields: Fields = fetcher
+                .placeholders()
+                .iter()
+                .map(|name| (*name, Value::from(placeholder)))
+                .collect();
+            vec![render(config, palette, fetcher, parsed_cfg, &fields)]
         }
-        "distro" => {
-            vars.insert("distro".to_string(), fetch::distro().distro);
-            vars.insert("arch".to_string(), fetch::distro().arch);
-            vars.insert("version".to_string(), fetch::distro().version);
-        }
-        "username" => {
-            vars.insert("user".to_string(), fetch::username().user);
-            vars.insert("host".to_string(), fetch::username().host);
-        }
-        "hostname" => {
-            vars.insert("host".to_string(), fetch::hostname().host);
-        }
-        "shell" => {
-            vars.insert("shell".to_string(), fetch::shell().shell);
-        }
-        "pkgs" => {
-            vars.insert("native".to_string(), fetch::pkgs().native);
-            vars.insert("flatpak".to_string(), fetch::pkgs().flatpak);
-            vars.insert("snap".to_string(), fetch::pkgs().snap);
-            vars.insert("manager".to_string(), fetch::pkgs().manager);
-        }
-        _ => {}
+    }
+}
+
+/// Rows of the 8 terminal colors, and their bright variants unless turned
+/// off in `[swatches]`.
+fn swatches(config: &Config) -> Vec<String> {
+    let swatches = config.swatches.as_ref();
+    let glyph = swatches.and_then(|s| s.glyph).unwrap_or('█');
+    let width = swatches.and_then(|s| s.width).unwrap_or(3);
+    let bright = swatches.and_then(|s| s.bright).unwrap_or(true);
+
+    let block = glyph.to_string().repeat(width);
+    let row = |base: u8| {
+        let mut row: String = (0..8)
+            .map(|i| format!("\u{1b}[{}m{block}", base + i))
+            .collect();
+        row.push_str("\u{1b}[0m");
+        row
+    };
+
+    if bright {
+        vec![row(30), row(90)]
+    } else {
+        vec![row(30)]
+    }
+}
+
+/// Collects the raw fields of every key in `keys` into a JSON object.
+/// List keys map to an array, failed keys to `{"error": ...}` and keys that
+/// timed out to `null`.
+pub fn fetch_json(config: &Config) -> serde_json::Value {
+    let mut cache = Cache::new(Arc::new(config.clone())).all_fields();
+
+    let fetchers: Vec<_> = config
+        .keys
+        .split(",")
+        .filter_map(|key| fetch::get(key.trim()))
+        .collect();
+    for &fetcher in &fetchers {
+        cache.spawn(fetcher);
+    }
+
+    let mut output = serde_json::Map::new();
+    for fetcher in fetchers {
+        let value = match cache.get(fetcher) {
+            Some(Ok(lines)) if fetcher.is_list() => serde_json::json!(lines),
+            Some(Ok(lines)) => serde_json::json!(lines.first()),
+            Some(Err(e)) => serde_json::json!({ "error": e.to_string() }),
+            None => serde_json::Value::Null,
+        };
+        output.insert(fetcher.name().to_string(), value);
     }
 
-    strfmt(parsed_cfg, &vars).unwrap()
+    serde_json::Value::Object(output)
 }
 
 pub fn fetch(config: &Config) -> Vec<String> {
     let mut fetch_text: Vec<String> = Vec::new();
     let mut separator_indices: Vec<usize> = Vec::new();
+    let mut cache = Cache::new(Arc::new(config.clone()));
+    let palette = Palette::new(config);
+
+    for key in config.keys.split(",") {
+        if let Some(fetcher) = fetch::get(key.trim()) {
+            cache.spawn(fetcher);
+        }
+    }
 
-    for (i, key) in config.keys.split(",").enumerate() {
+    for key in config.keys.split(",") {
         match key.trim() {
-            "batt" => fetch_text.push(format(config, "batt")),
-            "ram" => fetch_text.push(format(config, "ram")),
-            "swap" => fetch_text.push(format(config, "swap")),
-            "uptime" => fetch_text.push(format(config, "uptime")),
-            "lifetime" => fetch_text.push(format(config, "lifetime")),
-            "de" => fetch_text.push(format(config, "de")),
-            "kernel" => fetch_text.push(format(config, "kernel")),
-            "distro" => fetch_text.push(format(config, "distro")),
-            "username" => fetch_text.push(format(config, "username")),
-            "hostname" => fetch_text.push(format(config, "hostname")),
-            "shell" => fetch_text.push(format(config, "shell")),
-            "pkgs" => fetch_text.push(format(config, "pkgs")),
             "separator" => {
+                separator_indices.push(fetch_text.len());
                 fetch_text.push(String::new());
-                separator_indices.push(i)
             }
-            _ => fetch_text.push(key.to_string()),
+            "colors" => fetch_text.extend(swatches(config)),
+            name => match fetch::get(name) {
+                Some(fetcher) => fetch_text.extend(format(config, &palette, &mut cache, fetcher)),
+                None => fetch_text.push(palette.markup(key)),
+            },
         }
     }
 
@@ -183,7 +416,7 @@ pub fn fetch(config: &Config) -> Vec<String> {
         .iter()
         .enumerate()
         .filter(|(i, _)| !separator_indices.contains(i))
-        .map(|(_, s)| s.len())
+        .map(|(_, s)| display_width(s))
         .max()
         .unwrap_or(0);
 
@@ -192,6 +425,7 @@ pub fn fetch(config: &Config) -> Vec<String> {
         .unwrap_or('-')
         .to_string()
         .repeat(max_length);
+    let separator = palette.paint("separator", &separator);
 
     for &index in &separator_indices {
         fetch_text[index] = separator.clone();
@@ -199,3 +433,146 @@ pub fn fetch(config: &Config) -> Vec<String> {
 
     fetch_text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::{Unit, Value};
+
+    fn matches(condition: &str, value: Value) -> bool {
+        let fields = Fields::from([("x", value)]);
+        Condition::parse(condition).unwrap().matches(&fields)
+    }
+
+    #[test]
+    fn condition_parse() {
+        let condition = Condition::parse("  used_percentage>=80 ").unwrap();
+        assert_eq!(condition.placeholder, "used_percentage");
+        assert_eq!(condition.op, ">=");
+        assert_eq!(condition.value, "80");
+
+        let condition = Condition::parse(r#"status == "Charging""#).unwrap();
+        assert_eq!(condition.op, "==");
+        assert_eq!(condition.value, "Charging");
+
+        assert!(Condition::parse("used_percentage").is_none());
+        assert!(Condition::parse("used_percentage => 80").is_none());
+        assert!(Condition::parse("> 80").is_none());
+    }
+
+    #[test]
+    fn condition_operators() {
+        assert!(matches("x > 80", Value::Int(81)));
+        assert!(!matches("x > 80", Value::Int(80)));
+        assert!(matches("x >= 80", Value::Int(80)));
+        assert!(matches("x < 80", Value::Int(79)));
+        assert!(!matches("x < 80", Value::Int(80)));
+        assert!(matches("x <= 80", Value::Int(80)));
+        assert!(matches("x == 80", Value::Int(80)));
+        assert!(matches("x != 80", Value::Int(79)));
+        assert!(!matches("x != 80", Value::Int(80)));
+    }
+
+    #[test]
+    fn condition_compares_numbers_as_displayed() {
+        // 9 < 10 as numbers, but not as text.
+        assert!(matches("x < 10", Value::Int(9)));
+        assert!(matches("x == 80", Value::Float(80.0)));
+        assert!(matches("x > 2.5", Value::Float(2.6)));
+        // 3 GiB is shown as 3.0.
+        assert!(matches("x == 3", Value::Size(3 << 30, Unit::GiB)));
+        assert!(matches("x > 3000", Value::Size(3 << 30, Unit::MiB)));
+    }
+
+    #[test]
+    fn condition_compares_text() {
+        assert!(matches("x == Charging", Value::from("Charging")));
+        assert!(matches(r#"x != "Full""#, Value::from("Charging")));
+        assert!(!matches("x == charging", Value::from("Charging")));
+        // Ordering only applies to numbers.
+        assert!(!matches("x > a", Value::from("b")));
+        assert!(!matches("x < 10", Value::from("9 GiB")));
+    }
+
+    #[test]
+    fn condition_missing_placeholder() {
+        let fields = Fields::new();
+        assert!(!Condition::parse("x != 1").unwrap().matches(&fields));
+    }
+
+    fn apply(filter: &str, value: Value) -> String {
+        Filter::parse(filter).unwrap().apply(value).to_string()
+    }
+
+    #[test]
+    fn filter_units() {
+        let size = Value::Size(3 * (1 << 30) + (1 << 29), Unit::MiB);
+        assert_eq!(apply("gib", size.clone()), "4");
+        assert_eq!(apply("gib:1", size.clone()), "3.5");
+        assert_eq!(apply("gib:2", size.clone()), "3.50");
+        assert_eq!(apply("mib", size.clone()), "3584");
+        assert_eq!(apply("mib:1", Value::Size(1 << 19, Unit::KiB)), "0.5");
+        assert_eq!(apply("kib", Value::Size(1 << 20, Unit::GiB)), "1024");
+        assert_eq!(apply("tib:3", Value::Size(1 << 40, Unit::GiB)), "1.000");
+        assert_eq!(apply("bytes", size), "3758096384");
+    }
+
+    #[test]
+    fn filter_units_leave_other_values_alone() {
+        assert_eq!(apply("gib:1", Value::Int(42)), "42");
+        assert_eq!(apply("bytes", Value::from("unknown")), "unknown");
+    }
+
+    #[test]
+    fn filter_case() {
+        assert_eq!(apply("upper", Value::from("Arch Linux")), "ARCH LINUX");
+        assert_eq!(apply("lower", Value::from("Arch Linux")), "arch linux");
+    }
+
+    #[test]
+    fn filter_trunc_by_display_width() {
+        assert_eq!(apply("trunc:4", Value::from("corrfetch")), "corr");
+        assert_eq!(apply("trunc:20", Value::from("corrfetch")), "corrfetch");
+        // Wide characters take two columns.
+        assert_eq!(apply("trunc:5", Value::from("日本語です")), "日本");
+        assert_eq!(apply("trunc:0", Value::from("abc")), "");
+    }
+
+    #[test]
+    fn filter_pad_by_display_width() {
+        assert_eq!(apply("pad:6", Value::from("abc")), "abc   ");
+        assert_eq!(apply("lpad:6", Value::from("abc")), "   abc");
+        assert_eq!(apply("pad:6", Value::from("日本")), "日本  ");
+        assert_eq!(apply("lpad:5", Value::from("日本")), " 日本");
+        // Longer values are not cut.
+        assert_eq!(apply("pad:2", Value::from("abc")), "abc");
+        assert_eq!(apply("lpad:3", Value::Int(7)), "  7");
+    }
+
+    #[test]
+    fn filter_parse_errors() {
+        assert_eq!(
+            Filter::parse("kb").unwrap_err(),
+            "unknown filter `kb` (available: upper, lower, trunc, pad, lpad, kib, mib, gib, tib, bytes)"
+        );
+        assert_eq!(
+            Filter::parse("trunc").unwrap_err(),
+            "filter `trunc` needs a number, like `trunc:10`"
+        );
+        assert_eq!(
+            Filter::parse("gib:x").unwrap_err(),
+            "invalid argument `x` for filter `gib`"
+        );
+        assert!(Filter::parse("pad:-1").is_err());
+        assert!(Filter::parse(" gib : 2 ").is_ok());
+    }
+
+    #[test]
+    fn filters_in_format_string() {
+        assert_eq!(
+            filters_in("{used|gib:1}/{total|gib:1|lpad:6} {name|upper}"),
+            ["gib:1", "gib:1", "lpad:6", "upper"]
+        );
+        assert!(filters_in("{used} {bar:used_percentage}").is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+use std::io::{IsTerminal, stdout};
+use unicode_width::UnicodeWidthStr;
+
+/// Where the logo goes relative to the fetch text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Position {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// How output is put together. Text logos are composed with the fetch text
+/// into plain lines, so no cursor movement is needed and the output can be
+/// piped anywhere.
+pub struct Layout {
+    /// Whether stdout is a terminal, which image logos need.
+    pub tty: bool,
+    /// Whether to keep ANSI escapes in the output.
+    pub color: bool,
+    pub position: Position,
+    /// Columns (left/right) or lines (top/bottom) between logo and text.
+    pub gap: usize,
+    /// Columns before every line.
+    pub padding: usize,
+}
+
+fn exit_invalid(what: &str, value: &str, expected: &str) -> ! {
+    eprintln!("Invalid {what} `{value}`, expected {expected}!");
+    std::process::exit(1);
+}
+
+impl Layout {
+    /// `color` is one of auto, always or never; auto colors only terminals
+    /// and respects `$NO_COLOR`.
+    pub fn new(
+        color: Option<&str>,
+        position: Option<&str>,
+        gap: Option<u32>,
+        padding: Option<u32>,
+    ) -> Self {
+        let tty = stdout().is_terminal();
+
+        let color = match color {
+            Some("always") => true,
+            Some("never") => false,
+            Some("auto") | None => tty && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+            Some(other) => exit_invalid("color choice", other, "auto, always or never"),
+        };
+
+        let position = match position {
+            Some("left") | None => Position::Left,
+            Some("right") => Position::Right,
+            Some("top") => Position::Top,
+            Some("bottom") => Position::Bottom,
+            Some(other) => exit_invalid("logo position", other, "left, right, top or bottom"),
+        };
+
+        let gap = match (gap, position) {
+            (Some(gap), _) => gap as usize,
+            (None, Position::Left | Position::Right) => 2,
+            (None, Position::Top | Position::Bottom) => 1,
+        };
+
+        Layout {
+            tty,
+            color,
+            position,
+            gap,
+            padding: padding.unwrap_or(0) as usize,
+        }
+    }
+
+    /// Places `logo` next to `text` according to `position`, padding lines
+    /// by display width so columns line up.
+    pub fn compose(&self, logo: &str, text: &[String]) -> Vec<String> {
+        let logo: Vec<String> = logo.lines().map(String::from).collect();
+
+        let lines = if logo.is_empty() {
+            text.to_vec()
+        } else {
+            match self.position {
+                Position::Left => side_by_side(&logo, text, self.gap),
+                Position::Right => side_by_side(text, &logo, self.gap),
+                Position::Top => stacked(&logo, text, self.gap),
+                Position::Bottom => stacked(text, &logo, self.gap),
+            }
+        };
+
+        let padding = " ".repeat(self.padding);
+        lines
+            .into_iter()
+            .map(|line| format!("{padding}{line}"))
+            .collect()
+    }
+
+    pub fn print(&self, lines: &[String]) {
+        for line in lines {
+            if self.color {
+                println!("{line}");
+            } else {
+                println!("{}", console::strip_ansi_codes(line));
+            }
+        }
+    }
+}
+
+fn side_by_side(left: &[String], right: &[String], gap: usize) -> Vec<String> {
+    let width = left
+        .iter()
+        .map(|line| display_width(line))
+        .max()
+        .unwrap_or(0);
+
+    (0..left.len().max(right.len()))
+        .map(|i| {
+            let left_line = left.get(i).map_or("", |line| line.as_str());
+            let padding = " ".repeat(width - display_width(left_line) + gap);
+
+            match right.get(i) {
+                // Reset so colors on the left do not bleed into the right.
+                Some(right_line) if left_line.contains('\u{1b}') => {
+                    format!("{left_line}\u{1b}[0m{padding}{right_line}")
+                }
+                Some(right_line) => format!("{left_line}{padding}{right_line}"),
+                None => left_line.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn stacked(above: &[String], below: &[String], gap: usize) -> Vec<String> {
+    let mut lines = above.to_vec();
+    lines.extend(std::iter::repeat_n(String::new(), gap));
+    lines.extend_from_slice(below);
+    lines
+}
+
+pub fn display_width(s: &str) -> usize {
+    console::strip_ansi_codes(s).width()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(position: Position, gap: usize, padding: usize) -> Layout {
+        Layout {
+            tty: false,
+            color: false,
+            position,
+            gap,
+            padding,
+        }
+    }
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn no_logo() {
+        let text = lines(&["a", "b"]);
+        assert_eq!(
+            layout(Position::Left, 2, 1).compose("", &text),
+            [" a", " b"]
+        );
+    }
+
+    #[test]
+    fn logo_left_taller_than_text() {
+        let text = lines(&["user@host", "os"]);
+        assert_eq!(
+            layout(Position::Left, 2, 0).compose("/\\\n||||\n\\/", &text),
+            ["/\\    user@host", "||||  os", "\\/"]
+        );
+    }
+
+    #[test]
+    fn text_taller_than_logo() {
+        let text = lines(&["one", "two", "three"]);
+        assert_eq!(
+            layout(Position::Left, 1, 0).compose("ab", &text),
+            ["ab one", "   two", "   three"]
+        );
+        assert_eq!(
+            layout(Position::Right, 1, 0).compose("ab", &text),
+            ["one   ab", "two", "three"]
+        );
+    }
+
+    #[test]
+    fn ansi_colored_logo() {
+        let logo = "\u{1b}[31m##\u{1b}[0m\n\u{1b}[31m#\u{1b}[0m";
+        let text = lines(&["a", "b"]);
+        assert_eq!(
+            layout(Position::Left, 1, 0).compose(logo, &text),
+            [
+                "\u{1b}[31m##\u{1b}[0m\u{1b}[0m a",
+                "\u{1b}[31m#\u{1b}[0m\u{1b}[0m  b",
+            ]
+        );
+    }
+
+    #[test]
+    fn wide_characters() {
+        let text = lines(&["x", "y"]);
+        assert_eq!(
+            layout(Position::Left, 1, 0).compose("日本\na", &text),
+            ["日本 x", "a    y"]
+        );
+        assert_eq!(display_width("日本"), 4);
+        assert_eq!(display_width("\u{1b}[1m日本\u{1b}[0m"), 4);
+    }
+
+    #[test]
+    fn gap_and_padding() {
+        let text = lines(&["t"]);
+        assert_eq!(layout(Position::Left, 0, 0).compose("L", &text), ["Lt"]);
+        assert_eq!(
+            layout(Position::Left, 3, 2).compose("L", &text),
+            ["  L   t"]
+        );
+        assert_eq!(
+            layout(Position::Top, 2, 1).compose("L", &text),
+            [" L", " ", " ", " t"]
+        );
+        assert_eq!(
+            layout(Position::Bottom, 1, 0).compose("L", &text),
+            ["t", "", "L"]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::fetch;
+
+/// A bundled distro logo. `$1`, `$2`, ... in `art` switch to the matching
+/// entry of `colors` (ANSI SGR codes).
+pub struct Logo {
+    pub names: &'static [&'static str],
+    pub colors: &'static [&'static str],
+    pub art: &'static str,
+}
+
+pub static LOGOS: &[Logo] = &[
+    Logo {
+        names: &["arch", "archlinux"],
+        colors: &["1;36"],
+        art: r"$1       /\
+      /  \
+     /\   \
+    /      \
+   /   ,,   \
+  /   |  |  -\
+ /_-''    ''-_\",
+    },
+    Logo {
+        names: &["artix"],
+        colors: &["1;36"],
+        art: r"$1      /\
+     /  \
+    /`'.,\
+   /     ',
+  /      ,`\
+ /   ,.'`.  \
+/.,'`     `'.\",
+    },
+    Logo {
+        names: &["debian"],
+        colors: &["1;31"],
+        art: r"$1  _____
+ /  __ \
+|  /    |
+|  \___-
+-_
+  --_",
+    },
+    Logo {
+        names: &["ubuntu"],
+        colors: &["1;31", "1;37"],
+        art: r"$1         _
+     ---$2(_)
+$1 _/  ---  \
+$2(_)$1 |   |
+  \  --- _/
+     ---$2(_)",
+    },
+    Logo {
+        names: &["linuxmint", "mint"],
+        colors: &["1;32", "1;37"],
+        art: r"$1 _____________
+|_            \
+  |  $2| _____$1  |
+  |  $2| | | |$1  |
+  |  $2| | | |$1  |
+  |  $2\_____/$1  |
+  \___________/",
+    },
+    Logo {
+        names: &["fedora"],
+        colors: &["1;34", "1;37"],
+        art: r"$1      _____
+     /   __)$2\
+$1     |  /  \$2 \
+$1  ___|  |__/$2 /
+$1 / (_    _)_/
+/ /  |  |
+\ \__/  |
+ \(_____/",
+    },
+    Logo {
+        names: &["void"],
+        colors: &["1;32"],
+        art: r"$1    _______
+ _ \______ -
+| \  ___  \ |
+| | /   \ | |
+| | \___/ | |
+| \______ \_|
+ -_______\",
+    },
+    Logo {
+        names: &["gentoo"],
+        colors: &["1;35", "1;37"],
+        art: r"$1 _-----_
+(       \
+\    $20$1   \
+$2 \        )
+ /      _/
+(     _-
+\____-",
+    },
+    Logo {
+        names: &["nixos", "nix"],
+        colors: &["1;34", "1;36"],
+        art: r"$1  \\  $2\\ //
+$1 ==\\__$2\\/ //
+$1   //   \\//
+==//     //==
+ //\\___//
+// /\\  $2\\==
+$1  // \\  $2\\",
+    },
+    Logo {
+        names: &["manjaro"],
+        colors: &["1;32"],
+        art: r"$1||||||||| ||||
+||||||||| ||||
+||||      ||||
+|||| |||| ||||
+|||| |||| ||||
+|||| |||| ||||
+|||| |||| ||||",
+    },
+    Logo {
+        names: &["alpine"],
+        colors: &["1;34"],
+        art: r"$1   /\ /\
+  // \  \
+ //   \  \
+///    \  \
+//      \  \
+         \",
+    },
+    Logo {
+        names: &["linux"],
+        colors: &["1;37", "1;33"],
+        art: r"$1    ___
+   (.. |
+   ($2<>$1 |
+  / __  \
+ ( /  \ /|
+$2_$1/\ __)/$2_$1)
+$2\/$1-____$2\/",
+    },
+];
+
+pub fn get(name: &str) -> Option<&'static Logo> {
+    let name = name.to_lowercase();
+    LOGOS
+        .iter()
+        .find(|logo| logo.names.contains(&name.as_str()))
+}
+
+/// Picks the logo for the running distro from `ID`, then `ID_LIKE` in
+/// /etc/os-release, falling back to Tux.
+pub fn detect() -> &'static Logo {
+    let id = fetch::read_os_release("ID=", None).unwrap_or_default();
+    let id_like = fetch::read_os_release("ID_LIKE=", None).unwrap_or_default();
+
+    std::iter::once(id.as_str())
+        .chain(id_like.split_whitespace())
+        .find_map(get)
+        .or_else(|| get("linux"))
+        .expect("Tux logo is missing!")
+}
+
+impl Logo {
+    /// The logo with its color markers replaced by ANSI escapes. Each line
+    /// starts with the color the previous one ended in.
+    pub fn render(&self) -> String {
+        let mut ascii = String::new();
+        let mut color = "";
+
+        for line in self.art.lines() {
+            if !color.is_empty() {
+                ascii.push_str(&format!("\u{1b}[{color}m"));
+            }
+
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                let index = chars.peek().and_then(|n| n.to_digit(10));
+                match index {
+                    Some(i) if c == '$' && (i as usize) <= self.colors.len() && i > 0 => {
+                        chars.next();
+                        color = self.colors[i as usize - 1];
+                        ascii.push_str(&format!("\u{1b}[{color}m"));
+                    }
+                    _ => ascii.push(c),
+                }
+            }
+
+            ascii.push_str("\u{1b}[0m\n");
+        }
+
+        ascii
+    }
+}
@@ -1,8 +1,12 @@
 pub mod arg_parser;
 pub mod cfg_parser;
+pub mod check;
+pub mod color;
 pub mod display;
 pub mod fetch;
 pub mod format;
+pub mod layout;
+pub mod logos;
 
 fn main() {
     arg_parser::parse();